@@ -1,3 +1,29 @@
 pub mod constants {
     pub const STAKE_VAULT: &[u8] = b"stake_vault";
+    pub const VAULT_AUTHORITY: &[u8] = b"vault_authority";
+    pub const BID_ESCROW: &[u8] = b"bid_escrow";
+    pub const OFFER_ESCROW: &[u8] = b"offer_escrow";
+    pub const LEASE_ESCROW: &[u8] = b"lease_escrow";
+    pub const SWAP_ESCROW: &[u8] = b"swap_escrow";
+    pub const TREASURY_VAULT: &[u8] = b"treasury_vault";
+    pub const REWARD_VAULT: &[u8] = b"reward_vault";
+    pub const POOLED_VAULT: &[u8] = b"pooled_vault";
+    pub const DEFAULT_UNLOCK_COOLDOWN: i64 = 7 * 24 * 60 * 60;
+    pub const EARLY_UNLOCK_REASON: u16 = 0;
+
+    // Metaplex token metadata limits
+    pub const MAX_NAME_LENGTH: usize = 32;
+    pub const MAX_SYMBOL_LENGTH: usize = 10;
+    pub const MAX_URI_LENGTH: usize = 200;
+    pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+    // bit flags for CollectionConfig::allowed_uri_schemes
+    pub const URI_SCHEME_HTTPS: u8 = 1 << 0;
+    pub const URI_SCHEME_ARWEAVE: u8 = 1 << 1;
+    pub const URI_SCHEME_IPFS: u8 = 1 << 2;
+    pub const URI_SCHEMES: [(u8, &str); 3] = [
+        (URI_SCHEME_HTTPS, "https://"),
+        (URI_SCHEME_ARWEAVE, "ar://"),
+        (URI_SCHEME_IPFS, "ipfs://"),
+    ];
 }
\ No newline at end of file
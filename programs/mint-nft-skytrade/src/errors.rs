@@ -12,5 +12,191 @@ pub enum Errors {
     TokenAlreadyWhitelisted,
 
     #[msg("Token is not whitelisted")]
-    TokenNotWhitelisted
+    TokenNotWhitelisted,
+
+    #[msg("Collection max supply reached")]
+    MaxSupplyReached,
+
+    #[msg("Max supply is lower than the minted count")]
+    InvalidMaxSupply,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("Invalid tree config account")]
+    InvalidTreeConfig,
+
+    #[msg("Mint request already processed")]
+    DuplicateMintRequest,
+
+    #[msg("Name must not be empty")]
+    NameEmpty,
+
+    #[msg("Name exceeds 32 bytes")]
+    NameTooLong,
+
+    #[msg("Symbol exceeds 10 bytes")]
+    SymbolTooLong,
+
+    #[msg("Symbol does not match the collection symbol")]
+    SymbolMismatch,
+
+    #[msg("Uri exceeds 200 bytes")]
+    UriTooLong,
+
+    #[msg("Uri scheme is not allowed for this collection")]
+    UriSchemeNotAllowed,
+
+    #[msg("Invalid uri scheme flags")]
+    InvalidUriSchemes,
+
+    #[msg("Seller fee basis points exceed 10000")]
+    InvalidSellerFeeBasisPoints,
+
+    #[msg("Metadata could not be serialized")]
+    InvalidMetadata,
+
+    #[msg("Signer is neither the leaf owner nor the leaf delegate")]
+    NotOwnerOrDelegate,
+
+    #[msg("Funds are locked against this cNFT")]
+    CnftLocked,
+
+    #[msg("Only the leaf owner can carry the deposit over")]
+    NotDepositor,
+
+    #[msg("Fee basis points exceed 10000")]
+    InvalidFeeBasisPoints,
+
+    #[msg("Price must be greater than zero")]
+    InvalidPrice,
+
+    #[msg("Fees and royalties exceed the sale price")]
+    FeesExceedPrice,
+
+    #[msg("Listing price exceeds the buyer's maximum price")]
+    PriceAboveMax,
+
+    #[msg("Invalid auction parameters")]
+    InvalidAuctionParams,
+
+    #[msg("Auction has ended")]
+    AuctionEnded,
+
+    #[msg("Auction has not ended yet")]
+    AuctionNotEnded,
+
+    #[msg("Bid is below the reserve price or minimum increment")]
+    BidTooLow,
+
+    #[msg("Refund token account is missing or invalid")]
+    InvalidRefundAccount,
+
+    #[msg("Invalid dutch auction pricing")]
+    InvalidDutchPricing,
+
+    #[msg("Dutch listings can not be repriced")]
+    DutchListingNotUpdatable,
+
+    #[msg("Offer expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Offer has expired")]
+    OfferExpired,
+
+    #[msg("Offer has not expired yet")]
+    OfferNotExpired,
+
+    #[msg("Leaf does not match the offered asset")]
+    AssetMismatch,
+
+    #[msg("Parcel is leased")]
+    LeaseActive,
+
+    #[msg("Invalid lease terms")]
+    InvalidLeaseTerms,
+
+    #[msg("Lease has not ended yet")]
+    LeaseNotEnded,
+
+    #[msg("Invalid swap terms")]
+    InvalidSwapTerms,
+
+    #[msg("Swap has expired")]
+    SwapExpired,
+
+    #[msg("An account required by the swap terms is missing")]
+    MissingSwapAccount,
+
+    #[msg("Spending period must be positive")]
+    InvalidSpendingPeriod,
+
+    #[msg("Treasury spending limit exceeded for this period")]
+    SpendingLimitExceeded,
+
+    #[msg("Fee accounts are missing or do not match the treasury")]
+    InvalidFeeAccount,
+
+    #[msg("Unlock cooldown cannot be negative")]
+    InvalidUnlockCooldown,
+
+    #[msg("No unlock has been requested for this deposit")]
+    UnlockNotRequested,
+
+    #[msg("Unlock cooldown has not elapsed yet")]
+    UnlockCooldownActive,
+
+    #[msg("Pooled vault holds less than its ledger records")]
+    PooledVaultOutOfBalance,
+
+    #[msg("Deposit custody does not match the existing stake")]
+    CustodyMismatch,
+
+    #[msg("Funds are already locked for this deposit, use top_up_lock")]
+    AlreadyLocked,
+
+    #[msg("Invalid lock amount")]
+    InvalidLockAmount,
+
+    #[msg("Locked funds would fall below the asset price")]
+    BelowMinimumDeposit,
+
+    #[msg("Invalid penalty policy")]
+    InvalidPenaltyPolicy,
+
+    #[msg("Penalty record does not match the penalty owed")]
+    PenaltyRecordMismatch,
+
+    #[msg("Only the program upgrade authority can do this")]
+    NotUpgradeAuthority,
+
+    #[msg("The leaf can still be moved by this account")]
+    LeafNotStale,
+
+    #[msg("The bid still leads the auction")]
+    BidStillLeading,
+
+    #[msg("Winning bid accounts are missing or invalid")]
+    InvalidBidAccount,
+
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+
+    #[msg("Parcel has an open listing or auction")]
+    ParcelOnSale,
+
+    #[msg("Asset valuation account is invalid or values another asset")]
+    InvalidAssetInfo,
+
+    #[msg("Auction has not started yet")]
+    AuctionNotStarted,
+
+    #[msg("Winner does not match the highest bidder")]
+    NotAuctionWinner,
+
+    #[msg("Lease must be closed first")]
+    LeaseNotClosed,
+
+    #[msg("Deposit entry is closed")]
+    DepositNotOpen
 }
\ No newline at end of file
@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+use crate::state::PenaltyKind;
+
+#[event]
+pub struct ParcelMinted {
+    pub asset_id: Pubkey,
+    pub leaf_index: u32,
+    pub merkle_tree: Pubkey,
+    pub leaf_owner: Pubkey,
+    pub collection_mint: Pubkey,
+}
+
+#[event]
+pub struct ParcelMetadataUpdated {
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub old_data_hash: [u8; 32],
+    pub new_data_hash: [u8; 32],
+}
+
+#[event]
+pub struct CnftDelegated {
+    pub asset_id: Pubkey,
+    pub leaf_owner: Pubkey,
+    pub previous_leaf_delegate: Pubkey,
+    pub new_leaf_delegate: Pubkey,
+}
+
+#[event]
+pub struct CnftDelegateRevoked {
+    pub asset_id: Pubkey,
+    pub leaf_owner: Pubkey,
+    pub previous_leaf_delegate: Pubkey,
+}
+
+#[event]
+pub struct DepositCarriedOver {
+    pub asset_id: Pubkey,
+    pub depositor: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ParcelListed {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+    pub payment_mint: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub asset_id: Pubkey,
+    pub previous_price: u64,
+    pub price: u64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct ParcelSold {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub payment_mint: Pubkey,
+    pub price: u64,
+    pub royalty: u64,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct AuctionStarted {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+    pub payment_mint: Pubkey,
+    pub reserve_price: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub asset_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct BidWithdrawn {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+    pub winner: Option<Pubkey>,
+    pub amount: u64,
+    pub sold: bool,
+}
+
+#[event]
+pub struct OfferMade {
+    pub asset_id: Pubkey,
+    pub buyer: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct OfferClosed {
+    pub asset_id: Pubkey,
+    pub buyer: Pubkey,
+    pub refunded: u64,
+    pub expired: bool,
+}
+
+#[event]
+pub struct OfferAccepted {
+    pub asset_id: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub royalty: u64,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct LeaseCreated {
+    pub asset_id: Pubkey,
+    pub owner: Pubkey,
+    pub lessee: Pubkey,
+    pub payment_mint: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub rate_per_second: u64,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct LeaseRentClaimed {
+    pub asset_id: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct LeaseClosed {
+    pub asset_id: Pubkey,
+    pub owner: Pubkey,
+    pub lessee: Pubkey,
+    pub paid_to_owner: u64,
+    pub refunded_to_lessee: u64,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct SwapMade {
+    pub asset_id: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub requested_asset: Option<Pubkey>,
+    pub top_up_mint: Option<Pubkey>,
+    pub top_up_amount: u64,
+    pub top_up_paid_by_maker: bool,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SwapCancelled {
+    pub asset_id: Pubkey,
+    pub maker: Pubkey,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub asset_id: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub requested_asset: Option<Pubkey>,
+    pub top_up_amount: u64,
+}
+
+#[event]
+pub struct TreasuryConfigured {
+    pub mint: Pubkey,
+    pub spending_limit: u64,
+    pub period: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub mint: Pubkey,
+    pub treasurer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnlockRequested {
+    pub asset_id: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct UnlockWithdrawn {
+    pub asset_id: Pubkey,
+    pub depositor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub vault_closed: bool,
+}
+
+#[event]
+pub struct LockToppedUp {
+    pub asset_id: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct LockPartiallyUnlocked {
+    pub asset_id: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct RewardPoolUpdated {
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub emission_rate: u64,
+}
+
+#[event]
+pub struct RewardPoolFunded {
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub asset_id: Pubkey,
+    pub beneficiary: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    pub still_owed: u64,
+}
+
+#[event]
+pub struct PooledCustodyReconciled {
+    pub mint: Pubkey,
+    pub vault_balance: u64,
+    pub recorded: u64,
+    pub surplus: u64,
+}
+
+#[event]
+pub struct DepositSponsored {
+    pub asset_id: Pubkey,
+    pub sponsor: Pubkey,
+    pub owner: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PenaltyRecorded {
+    pub index: u64,
+    pub kind: PenaltyKind,
+    pub asset_id: Pubkey,
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct PenaltyPolicyUpdated {
+    pub arbitrator: Pubkey,
+    pub min_lock_duration: i64,
+    pub early_unlock_penalty_bps: u16,
+}
+
+#[event]
+pub struct AssetValued {
+    pub asset_id: Pubkey,
+    pub price: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct LegacyVaultWithdrawn {
+    pub asset_id: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
\ No newline at end of file
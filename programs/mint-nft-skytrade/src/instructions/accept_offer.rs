@@ -0,0 +1,218 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Mint, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::OfferAccepted;
+use crate::state::{Auction, CollectionConfig, Config, Listing, Offer, ParcelMetadata, ParcelState};
+use crate::utils::bps_amount;
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    /// the current leaf owner
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = buyer,
+        has_one = payment_mint,
+        seeds = [Offer::SEED.as_bytes(), offer.asset_id.as_ref(), offer.buyer.as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    #[account(
+        mut,
+        seeds = [constants::OFFER_ESCROW, offer.key().as_ref()],
+        bump
+    )]
+    pub offer_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    /// CHECK: checked against the offer
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = collection_config.authority
+    )]
+    pub royalty_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), offer.asset_id.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [Listing::SEED.as_bytes(), offer.asset_id.as_ref()],
+        bump
+    )]
+    /// CHECK: must not be initialized, the parcel can't be listed while an offer is accepted
+    pub listing: UncheckedAccount<'info>,
+    #[account(
+        seeds = [Auction::SEED.as_bytes(), offer.asset_id.as_ref()],
+        bump
+    )]
+    /// CHECK: must not be initialized, the parcel can't be auctioned while an offer is accepted
+    pub auction: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn accept_offer<'info>(ctx: Context<'_, '_, '_, 'info, AcceptOffer<'info>>,
+                           root: [u8; 32],
+                           nonce: u64,
+                           index: u32,
+                           metadata: ParcelMetadata) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    require!(!offer.is_expired(Clock::get()?.unix_timestamp), Errors::OfferExpired);
+    require_keys_eq!(get_asset_id(&ctx.accounts.merkle_tree.key(), nonce), offer.asset_id, Errors::AssetMismatch);
+    ParcelState::ensure_transferable(&ctx.accounts.parcel_state)?;
+    // cancel_listing, cancel_auction or close_stale_listing close these first
+    require!(ctx.accounts.listing.data_is_empty() && ctx.accounts.auction.data_is_empty(), Errors::ParcelOnSale);
+
+    // the transfer cpi verifies these hashes against the tree, which
+    // proves the royalty supplied in the metadata
+    let data_hash = hash_metadata(&metadata.to_metadata_args(ctx.accounts.collection_config.collection_mint))
+        .map_err(|_| Errors::InvalidMetadata)?;
+    let creator_hash = hash_creators(&[]);
+
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    TransferCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.seller.to_account_info(), true)
+        .leaf_delegate(&ctx.accounts.leaf_delegate.to_account_info(), false)
+        .new_leaf_owner(&ctx.accounts.buyer.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .invoke()?;
+
+    let protocol_fee = bps_amount(offer.amount, ctx.accounts.config.fees.sale_bps)?;
+    let royalty = bps_amount(offer.amount, metadata.seller_fee_basis_points)?;
+    // anyone can send tokens to the escrow; they go to the seller with the
+    // proceeds so the escrow is always empty when it is closed
+    let stray = ctx.accounts.offer_escrow.amount
+        .checked_sub(offer.amount)
+        .ok_or(Errors::MathOverflow)?;
+    let seller_proceeds = offer.amount
+        .checked_sub(protocol_fee)
+        .and_then(|amount| amount.checked_sub(royalty))
+        .ok_or(Errors::FeesExceedPrice)?
+        .checked_add(stray)
+        .ok_or(Errors::MathOverflow)?;
+
+    let offer_seeds: &[&[&[u8]]] = &[&[
+        Offer::SEED.as_bytes(),
+        offer.asset_id.as_ref(),
+        offer.buyer.as_ref(),
+        &[offer.bump]
+    ]];
+    for (destination, amount) in [
+        (&ctx.accounts.seller_token_account, seller_proceeds),
+        (&ctx.accounts.royalty_token_account, royalty),
+        (&ctx.accounts.treasury_vault, protocol_fee),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.offer_escrow.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: offer.to_account_info()
+                },
+                offer_seeds
+            ),
+            amount
+        )?;
+    }
+
+    //close escrow account
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.offer_escrow.to_account_info(),
+                destination: ctx.accounts.buyer.to_account_info(),
+                authority: offer.to_account_info()
+            },
+            offer_seeds
+        )
+    )?;
+
+    emit!(OfferAccepted {
+        asset_id: offer.asset_id,
+        seller: ctx.accounts.seller.key(),
+        buyer: offer.buyer,
+        payment_mint: offer.payment_mint,
+        amount: offer.amount,
+        royalty,
+        protocol_fee,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::SwapExecuted;
+use crate::state::{ParcelState, SwapOffer, TokenWhitelist};
+
+/// The taker's side of a swap. Only needed when the maker asked for a cNFT.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TakerLeaf {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSwap<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = taker,
+        has_one = merkle_tree,
+        seeds = [SwapOffer::SEED.as_bytes(), swap_offer.asset_id.as_ref()],
+        bump = swap_offer.bump
+    )]
+    pub swap_offer: Box<Account<'info, SwapOffer>>,
+
+    #[account(mut)]
+    /// CHECK: checked against the swap offer
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), swap_offer.asset_id.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub taker_leaf_delegate: Option<UncheckedAccount<'info>>,
+    /// CHECK: checked against the requested asset in the handler
+    pub taker_parcel_state: Option<UncheckedAccount<'info>>,
+    /// CHECK: This account is checked in the downstream program
+    pub taker_tree_authority: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub taker_merkle_tree: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [constants::SWAP_ESCROW, swap_offer.key().as_ref()],
+        bump
+    )]
+    pub swap_escrow: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = taker
+    )]
+    pub taker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = maker
+    )]
+    pub maker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Box<Account<'info, TokenWhitelist>>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+/// Settles both legs of a swap atomically. The remaining accounts hold
+/// the maker's proof followed by the taker's proof; `maker_proof_len`
+/// marks where one ends.
+pub fn accept_swap<'info>(ctx: Context<'_, '_, '_, 'info, AcceptSwap<'info>>,
+                          root: [u8; 32],
+                          taker_leaf: Option<TakerLeaf>,
+                          maker_proof_len: u8) -> Result<()> {
+    let swap_offer = &ctx.accounts.swap_offer;
+    require!(!swap_offer.is_expired(Clock::get()?.unix_timestamp), Errors::SwapExpired);
+    ParcelState::ensure_transferable(&ctx.accounts.parcel_state)?;
+
+    let (maker_proof, taker_proof) = ctx.remaining_accounts
+        .split_at((maker_proof_len as usize).min(ctx.remaining_accounts.len()));
+    let maker_proof_accounts: Vec<(&AccountInfo, bool, bool)> = maker_proof
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+    let taker_proof_accounts: Vec<(&AccountInfo, bool, bool)> = taker_proof
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    let swap_seeds: &[&[&[u8]]] = &[&[
+        SwapOffer::SEED.as_bytes(),
+        swap_offer.asset_id.as_ref(),
+        &[swap_offer.bump]
+    ]];
+
+    // the swap pda is the leaf delegate and signs the maker's leg
+    TransferCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.maker.to_account_info(), false)
+        .leaf_delegate(&swap_offer.to_account_info(), true)
+        .new_leaf_owner(&ctx.accounts.taker.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&maker_proof_accounts)
+        .root(root)
+        .data_hash(swap_offer.data_hash)
+        .creator_hash(swap_offer.creator_hash)
+        .nonce(swap_offer.nonce)
+        .index(swap_offer.index)
+        .invoke_signed(swap_seeds)?;
+
+    if let Some(requested_asset) = swap_offer.requested_asset {
+        let taker_leaf = taker_leaf.ok_or(Errors::MissingSwapAccount)?;
+        let (Some(taker_leaf_delegate), Some(taker_parcel_state), Some(taker_tree_authority), Some(taker_merkle_tree)) = (
+            ctx.accounts.taker_leaf_delegate.as_ref(),
+            ctx.accounts.taker_parcel_state.as_ref(),
+            ctx.accounts.taker_tree_authority.as_ref(),
+            ctx.accounts.taker_merkle_tree.as_ref(),
+        ) else {
+            return err!(Errors::MissingSwapAccount);
+        };
+        require_keys_eq!(get_asset_id(&taker_merkle_tree.key(), taker_leaf.nonce), requested_asset, Errors::AssetMismatch);
+        let (taker_parcel_state_key, _) = Pubkey::find_program_address(
+            &[ParcelState::SEED.as_bytes(), requested_asset.as_ref()],
+            ctx.program_id
+        );
+        require_keys_eq!(taker_parcel_state.key(), taker_parcel_state_key, Errors::AssetMismatch);
+        ParcelState::ensure_transferable(taker_parcel_state)?;
+
+        TransferCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+            .tree_config(&taker_tree_authority.to_account_info())
+            .leaf_owner(&ctx.accounts.taker.to_account_info(), true)
+            .leaf_delegate(&taker_leaf_delegate.to_account_info(), false)
+            .new_leaf_owner(&ctx.accounts.maker.to_account_info())
+            .merkle_tree(&taker_merkle_tree.to_account_info())
+            .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+            .compression_program(&ctx.accounts.compression_program.to_account_info())
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .add_remaining_accounts(&taker_proof_accounts)
+            .root(taker_leaf.root)
+            .data_hash(taker_leaf.data_hash)
+            .creator_hash(taker_leaf.creator_hash)
+            .nonce(taker_leaf.nonce)
+            .index(taker_leaf.index)
+            .invoke()?;
+    }
+
+    if let Some(top_up) = swap_offer.top_up {
+        require!(ctx.accounts.whitelist.tokens.contains(&top_up.payment_mint), Errors::TokenNotWhitelisted);
+        if top_up.paid_by_maker {
+            let swap_escrow = ctx.accounts.swap_escrow.as_ref().ok_or(Errors::MissingSwapAccount)?;
+            let taker_token_account = ctx.accounts.taker_token_account.as_ref().ok_or(Errors::MissingSwapAccount)?;
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: swap_escrow.to_account_info(),
+                        to: taker_token_account.to_account_info(),
+                        authority: swap_offer.to_account_info()
+                    },
+                    swap_seeds
+                ),
+                top_up.amount
+            )?;
+
+            //close escrow account
+            close_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    CloseAccount {
+                        account: swap_escrow.to_account_info(),
+                        destination: ctx.accounts.maker.to_account_info(),
+                        authority: swap_offer.to_account_info()
+                    },
+                    swap_seeds
+                )
+            )?;
+        } else {
+            let taker_token_account = ctx.accounts.taker_token_account.as_ref().ok_or(Errors::MissingSwapAccount)?;
+            let maker_token_account = ctx.accounts.maker_token_account.as_ref().ok_or(Errors::MissingSwapAccount)?;
+            require_keys_eq!(maker_token_account.mint, top_up.payment_mint, Errors::InvalidSwapTerms);
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: taker_token_account.to_account_info(),
+                        to: maker_token_account.to_account_info(),
+                        authority: ctx.accounts.taker.to_account_info()
+                    }
+                ),
+                top_up.amount
+            )?;
+        }
+    }
+
+    emit!(SwapExecuted {
+        asset_id: swap_offer.asset_id,
+        maker: swap_offer.maker,
+        taker: swap_offer.taker,
+        requested_asset: swap_offer.requested_asset,
+        top_up_amount: swap_offer.top_up.map_or(0, |top_up| top_up.amount),
+    });
+
+    Ok(())
+}
\ No newline at end of file
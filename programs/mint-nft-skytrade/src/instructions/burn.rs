@@ -1,33 +1,55 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create_idempotent, get_associated_token_address, AssociatedToken, Create};
 use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::hash::hash_metadata;
 use mpl_bubblegum::instructions::BurnCpiBuilder;
+use mpl_bubblegum::types::LeafSchema;
+use mpl_bubblegum::utils::get_asset_id;
 use spl_account_compression::Noop;
 use spl_account_compression::program::SplAccountCompression;
 use crate::MplBubblegum;
-// use crate::state::{StakeInfo, StakeInfoAccount};
+use crate::state::{CollectionConfig, ParcelMetadata, ParcelState, PooledCustody, RewardPool, StakeInfo};
 use crate::constants::*;
+use crate::errors::Errors;
+use crate::utils::{close_account_info, verify_leaf};
 
 #[derive(Accounts)]
+#[instruction(root: [u8; 32], metadata: ParcelMetadata, creator_hash: [u8; 32], nonce: u64)]
 pub struct BurnCNFT<'info> {
+    /// the leaf owner, proven against the tree before any funds move
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: only hashed into the leaf that is verified against the tree
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: the asset id of the leaf being burned
+    #[account(address = get_asset_id(&merkle_tree.key(), nonce))]
+    pub cnft: UncheckedAccount<'info>,
+
     #[account(
-        mut,
-        address = cnft.owner.key()
+        seeds = [constants::VAULT_AUTHORITY],
+        bump
     )]
-    pub signer: Signer<'info>,
+    /// CHECK: signs for every stake vault, holds no data
+    pub vault_authority: UncheckedAccount<'info>,
 
-    // Todo: remember to close this account
     #[account(
         mut,
-        seeds = [constants::STAKE_VAULT, cnft.key().as_ref()],
-        bump,
+        seeds = [ParcelState::SEED.as_bytes(), cnft.key().as_ref()],
+        bump
     )]
-    pub cnft_stake_vault: Account<'info, TokenAccount>,
-
-    pub signer_token_account: Account<'info, TokenAccount>,
+    /// CHECK: only initialized once funds were locked or the parcel leased,
+    /// closed to the signer when it exists
+    pub parcel_state: UncheckedAccount<'info>,
 
-    /// CHECK: should be vetted from front end
-    /// ensure this nft is owned by the signer
-    pub cnft: UncheckedAccount<'info>,
+    /// tied to the leaf through the collection hashed into its metadata
+    #[account(
+        mut,
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
 
     #[account(mut)]
     /// CHECKED: this account is checked in the instruction
@@ -38,54 +60,189 @@ pub struct BurnCNFT<'info> {
     pub compression_program: Program<'info, SplAccountCompression>,
     pub bubblegum_program: Program<'info, MplBubblegum>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>
 }
 
 pub fn burn_cnft<'info>(ctx: Context<'_, '_, '_, 'info, BurnCNFT<'info>>,
                         root: [u8; 32],
-                        data_hash: [u8; 32],
+                        metadata: ParcelMetadata,
                         creator_hash: [u8; 32],
                         nonce: u64, index: u32) -> Result<()> {
-    //withdraw funds from vault
-    let cnft_key = ctx.accounts.cnft_stake_vault.key();
-    let seed: &[&[&[u8]]] = &[&[cnft_key.as_ref(), &[ctx.bumps.cnft_stake_vault]]];
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.cnft_stake_vault.to_account_info(),
-                to: ctx.accounts.signer_token_account.to_account_info(),
-                authority: ctx.accounts.cnft_stake_vault.to_account_info()
-            },
-            seed
-        ),
-        ctx.accounts.cnft_stake_vault.amount
-    )?;
+    // a parcel that was never locked or leased has no ledger to refund
+    let parcel_state = ParcelState::load(&ctx.accounts.parcel_state)?;
+    if let Some(parcel_state) = &parcel_state {
+        require!(!parcel_state.is_leased(Clock::get()?.unix_timestamp), Errors::LeaseActive);
+        // burning closes the parcel state close_lease needs to release the escrow
+        require!(!parcel_state.has_open_lease(), Errors::LeaseNotClosed);
+    }
+
+    // the remaining accounts hold the merkle proof followed by one
+    // (stake info, vault, beneficiary's associated token account, beneficiary,
+    // mint, depositor, reward pool, pooled custody, signer's associated token
+    // account) group per staker
+    let ledger_len = parcel_state.as_ref().map_or(0, |parcel_state| parcel_state.stakers as usize * 9);
+    require!(ctx.remaining_accounts.len() >= ledger_len, Errors::InvalidRefundAccount);
+    let (proof, ledger) = ctx.remaining_accounts.split_at(ctx.remaining_accounts.len() - ledger_len);
 
-    //close vault account
-    close_account(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            CloseAccount {
-                account: ctx.accounts.cnft_stake_vault.to_account_info(),
-                destination: ctx.accounts.signer_token_account.to_account_info(),
-                authority: ctx.accounts.cnft_stake_vault.to_account_info()
-            },
-            seed
-        )
+    // the signer must own the leaf before any vault is released, and the
+    // leaf must belong to the collection whose counters the burn updates
+    let data_hash = hash_metadata(&metadata.to_metadata_args(ctx.accounts.collection_config.collection_mint))
+        .map_err(|_| Errors::InvalidMetadata)?;
+    let leaf = LeafSchema::V1 {
+        id: ctx.accounts.cnft.key(),
+        owner: ctx.accounts.signer.key(),
+        delegate: ctx.accounts.leaf_delegate.key(),
+        nonce,
+        data_hash,
+        creator_hash,
+    };
+    verify_leaf(
+        &ctx.accounts.compression_program.to_account_info(),
+        &ctx.accounts.merkle_tree.to_account_info(),
+        proof,
+        root,
+        &leaf,
+        index
     )?;
 
+    //refund every deposit to its beneficiary
+    let cnft = ctx.accounts.cnft.key();
+    let now = Clock::get()?.unix_timestamp;
+    let vault_seeds: &[&[&[u8]]] = &[&[constants::VAULT_AUTHORITY, &[ctx.bumps.vault_authority]]];
+    let create_ata = |associated_token: &AccountInfo<'info>, authority: &AccountInfo<'info>, mint: &AccountInfo<'info>| {
+        create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.signer.to_account_info(),
+                associated_token: associated_token.to_account_info(),
+                authority: authority.to_account_info(),
+                mint: mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            }
+        ))
+    };
+    let mut vaults: Vec<(&AccountInfo<'info>, &AccountInfo<'info>, &AccountInfo<'info>)> = Vec::new();
+    let mut refunded: u64 = 0;
+    for entry in ledger.chunks(9) {
+        let (stake_info_account, vault, refund_account, beneficiary, mint, depositor, reward_pool_account,
+            pooled_custody_account, sweep_account) =
+            (&entry[0], &entry[1], &entry[2], &entry[3], &entry[4], &entry[5], &entry[6], &entry[7], &entry[8]);
+        let mut stake_info = StakeInfo::load(stake_info_account, &cnft, ctx.program_id)?;
+        require!(stake_info.is_open(), Errors::InvalidRefundAccount);
+        require_keys_eq!(depositor.key(), stake_info.depositor, Errors::InvalidRefundAccount);
+        require!(beneficiary.key() == stake_info.beneficiary && mint.key() == stake_info.mint,
+            Errors::InvalidRefundAccount);
+
+        // refunds go to the beneficiary's associated token account, created
+        // here if needed, so closing it can't hold up the burn
+        require_keys_eq!(refund_account.key(), get_associated_token_address(beneficiary.key, mint.key),
+            Errors::InvalidRefundAccount);
+        create_ata(refund_account, beneficiary, mint)?;
+
+        let vault_key = stake_info.vault_address(ctx.program_id);
+        require_keys_eq!(vault.key(), vault_key, Errors::InvalidRefundAccount);
+        if !stake_info.pooled && !vaults.iter().any(|(known, _, _)| known.key() == vault_key) {
+            require_keys_eq!(sweep_account.key(), get_associated_token_address(ctx.accounts.signer.key, mint.key),
+                Errors::InvalidRefundAccount);
+            vaults.push((vault, mint, sweep_account));
+        }
+
+        if stake_info.amount > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: vault.to_account_info(),
+                        to: refund_account.to_account_info(),
+                        authority: ctx.accounts.vault_authority.to_account_info()
+                    },
+                    vault_seeds
+                ),
+                stake_info.amount
+            )?;
+        }
+        refunded = refunded.checked_add(stake_info.amount).ok_or(Errors::MathOverflow)?;
+
+        let (reward_pool_key, _) = Pubkey::find_program_address(
+            &[RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+            ctx.program_id
+        );
+        require_keys_eq!(reward_pool_account.key(), reward_pool_key, Errors::InvalidRefundAccount);
+        if let Some(mut reward_pool) = RewardPool::load(reward_pool_account)? {
+            reward_pool.update(now)?;
+            reward_pool.close_stake(&mut stake_info)?;
+            reward_pool.save(reward_pool_account)?;
+        }
+
+        if stake_info.pooled {
+            let (pooled_custody_key, _) = Pubkey::find_program_address(
+                &[PooledCustody::SEED.as_bytes(), stake_info.mint.as_ref()],
+                ctx.program_id
+            );
+            require_keys_eq!(pooled_custody_account.key(), pooled_custody_key, Errors::InvalidRefundAccount);
+            let mut pooled_custody = PooledCustody::load(pooled_custody_account)?
+                .ok_or(Errors::CustodyMismatch)?;
+            pooled_custody.record_withdrawal(stake_info.amount, true)?;
+            pooled_custody.reconcile(TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?.amount)?;
+            pooled_custody.save(pooled_custody_account)?;
+        }
+
+        // emptying the entry stops it from being refunded twice; it is only
+        // kept while rewards are still owed on it
+        stake_info.record_exit();
+        if stake_info.has_rewards_owed() {
+            stake_info.save(stake_info_account)?;
+        } else {
+            close_account_info(stake_info_account, depositor)?;
+        }
+    }
+    require!(refunded == parcel_state.as_ref().map_or(0, |parcel_state| parcel_state.locked_amount),
+        Errors::InvalidRefundAccount);
+
+    //sweep tokens sent to the per-cNFT vaults outside lock_fund to the owner, then close them
+    for (vault, mint, sweep_account) in vaults {
+        let stray = TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?.amount;
+        if stray > 0 {
+            create_ata(sweep_account, &ctx.accounts.signer.to_account_info(), mint)?;
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: vault.to_account_info(),
+                        to: sweep_account.to_account_info(),
+                        authority: ctx.accounts.vault_authority.to_account_info()
+                    },
+                    vault_seeds
+                ),
+                stray
+            )?;
+        }
+        close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: ctx.accounts.signer.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info()
+                },
+                vault_seeds
+            )
+        )?;
+    }
+
     //burn nft
-    let remaining_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+    let remaining_accounts: Vec<(&AccountInfo, bool, bool)> = proof
         .iter()
-        .map(|account| (account, account.is_signer, account.is_writable))
+        .map(|account| (account, false, false))
         .collect();
 
     BurnCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
         .tree_config(&ctx.accounts.tree_config.to_account_info())
         .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
         .leaf_owner(&ctx.accounts.signer.to_account_info(), true)
-        .leaf_delegate(&ctx.accounts.signer.to_account_info(), true)
+        .leaf_delegate(&ctx.accounts.leaf_delegate.to_account_info(), false)
         .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
         .compression_program(&ctx.accounts.compression_program.to_account_info())
         .system_program(&ctx.accounts.system_program.to_account_info())
@@ -97,5 +254,9 @@ pub fn burn_cnft<'info>(ctx: Context<'_, '_, '_, 'info, BurnCNFT<'info>>,
         .index(index)
         .invoke()?;
 
-    Ok(())
+    if parcel_state.is_some() {
+        close_account_info(&ctx.accounts.parcel_state, &ctx.accounts.signer)?;
+    }
+
+    ctx.accounts.collection_config.record_burn()
 }
\ No newline at end of file
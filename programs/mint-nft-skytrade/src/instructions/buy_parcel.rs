@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::ParcelSold;
+use crate::state::{CollectionConfig, Config, Listing, ParcelState, TokenWhitelist};
+use crate::utils::bps_amount;
+
+#[derive(Accounts)]
+pub struct BuyParcel<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        has_one = merkle_tree,
+        has_one = payment_mint,
+        seeds = [Listing::SEED.as_bytes(), listing.asset_id.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(mut)]
+    /// CHECK: checked against the listing
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [CollectionConfig::SEED.as_bytes(), listing.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Box<Account<'info, TokenWhitelist>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = collection_config.authority
+    )]
+    pub royalty_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), listing.asset_id.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn buy_parcel<'info>(ctx: Context<'_, '_, '_, 'info, BuyParcel<'info>>,
+                         root: [u8; 32],
+                         max_price: u64) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let price = listing.current_price(Clock::get()?.unix_timestamp)?;
+    require!(price <= max_price, Errors::PriceAboveMax);
+    require!(ctx.accounts.whitelist.tokens.contains(&listing.payment_mint), Errors::TokenNotWhitelisted);
+    ParcelState::ensure_transferable(&ctx.accounts.parcel_state)?;
+
+    let protocol_fee = bps_amount(price, ctx.accounts.config.fees.sale_bps)?;
+    let royalty = bps_amount(price, listing.royalty_bps)?;
+    let seller_proceeds = price
+        .checked_sub(protocol_fee)
+        .and_then(|amount| amount.checked_sub(royalty))
+        .ok_or(Errors::FeesExceedPrice)?;
+
+    for (destination, amount) in [
+        (&ctx.accounts.seller_token_account, seller_proceeds),
+        (&ctx.accounts.royalty_token_account, royalty),
+        (&ctx.accounts.treasury_vault, protocol_fee),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_token_account.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info()
+                }
+            ),
+            amount
+        )?;
+    }
+
+    // the listing pda is the leaf delegate and signs the transfer to the buyer
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    TransferCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.seller.to_account_info(), false)
+        .leaf_delegate(&listing.to_account_info(), true)
+        .new_leaf_owner(&ctx.accounts.buyer.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(listing.data_hash)
+        .creator_hash(listing.creator_hash)
+        .nonce(listing.nonce)
+        .index(listing.index)
+        .invoke_signed(&[&[
+            Listing::SEED.as_bytes(),
+            listing.asset_id.as_ref(),
+            &[listing.bump]
+        ]])?;
+
+    emit!(ParcelSold {
+        asset_id: listing.asset_id,
+        seller: listing.seller,
+        buyer: ctx.accounts.buyer.key(),
+        payment_mint: listing.payment_mint,
+        price,
+        royalty,
+        protocol_fee,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::errors::Errors;
+use crate::events::AuctionCancelled;
+use crate::state::Auction;
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        has_one = merkle_tree,
+        seeds = [Auction::SEED.as_bytes(), auction.asset_id.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraws an auction nobody has bid on. Pass no root when the seller no
+/// longer holds the leaf; the stale delegate can no longer sign once the
+/// auction account is closed.
+pub fn cancel_auction<'info>(ctx: Context<'_, '_, '_, 'info, CancelAuction<'info>>, root: Option<[u8; 32]>) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    require!(auction.highest_bidder.is_none(), Errors::AuctionHasBids);
+
+    if let Some(root) = root {
+        let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+            .iter()
+            .map(|account| (account, false, false))
+            .collect();
+
+        // hand the delegate role back to the seller
+        DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+            .tree_config(&ctx.accounts.tree_authority.to_account_info())
+            .leaf_owner(&ctx.accounts.seller.to_account_info())
+            .previous_leaf_delegate(&auction.to_account_info())
+            .new_leaf_delegate(&ctx.accounts.seller.to_account_info())
+            .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+            .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+            .compression_program(&ctx.accounts.compression_program.to_account_info())
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .add_remaining_accounts(&proof_accounts)
+            .root(root)
+            .data_hash(auction.data_hash)
+            .creator_hash(auction.creator_hash)
+            .nonce(auction.nonce)
+            .index(auction.index)
+            .invoke()?;
+    }
+
+    emit!(AuctionCancelled {
+        asset_id: auction.asset_id,
+        seller: auction.seller,
+    });
+
+    Ok(())
+}
\ No newline at end of file
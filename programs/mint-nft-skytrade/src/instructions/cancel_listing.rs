@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::events::ListingCancelled;
+use crate::state::Listing;
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        has_one = merkle_tree,
+        seeds = [Listing::SEED.as_bytes(), listing.asset_id.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pass no root when the seller no longer holds the leaf; the stale
+/// delegate can no longer sign once the listing account is closed.
+pub fn cancel_listing<'info>(ctx: Context<'_, '_, '_, 'info, CancelListing<'info>>, root: Option<[u8; 32]>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    if let Some(root) = root {
+        let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+            .iter()
+            .map(|account| (account, false, false))
+            .collect();
+
+        // hand the delegate role back to the seller
+        DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+            .tree_config(&ctx.accounts.tree_authority.to_account_info())
+            .leaf_owner(&ctx.accounts.seller.to_account_info())
+            .previous_leaf_delegate(&listing.to_account_info())
+            .new_leaf_delegate(&ctx.accounts.seller.to_account_info())
+            .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+            .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+            .compression_program(&ctx.accounts.compression_program.to_account_info())
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .add_remaining_accounts(&proof_accounts)
+            .root(root)
+            .data_hash(listing.data_hash)
+            .creator_hash(listing.creator_hash)
+            .nonce(listing.nonce)
+            .index(listing.index)
+            .invoke()?;
+    }
+
+    emit!(ListingCancelled {
+        asset_id: listing.asset_id,
+        seller: listing.seller,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::events::OfferClosed;
+use crate::state::Offer;
+
+/// Accounts for giving an offer's escrow back to the buyer, used both by
+/// the buyer cancelling and by anyone cranking an expired offer.
+#[derive(Accounts)]
+pub struct CloseOffer<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = buyer,
+        seeds = [Offer::SEED.as_bytes(), offer.asset_id.as_ref(), offer.buyer.as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        seeds = [constants::OFFER_ESCROW, offer.key().as_ref()],
+        bump
+    )]
+    pub offer_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    /// CHECK: checked against the offer
+    pub buyer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = offer.payment_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>
+}
+
+pub fn cancel_offer(ctx: Context<CloseOffer>) -> Result<()> {
+    require_keys_eq!(ctx.accounts.signer.key(), ctx.accounts.offer.buyer);
+    refund_offer(ctx, false)
+}
+
+pub(crate) fn refund_offer(ctx: Context<CloseOffer>, expired: bool) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let offer_seeds: &[&[&[u8]]] = &[&[
+        Offer::SEED.as_bytes(),
+        offer.asset_id.as_ref(),
+        offer.buyer.as_ref(),
+        &[offer.bump]
+    ]];
+    // the whole balance, including anything sent to the escrow since the
+    // offer was made, so it can always be closed
+    let refunded = ctx.accounts.offer_escrow.amount;
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.offer_escrow.to_account_info(),
+                to: ctx.accounts.buyer_token_account.to_account_info(),
+                authority: offer.to_account_info()
+            },
+            offer_seeds
+        ),
+        refunded
+    )?;
+
+    //close escrow account
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.offer_escrow.to_account_info(),
+                destination: ctx.accounts.buyer.to_account_info(),
+                authority: offer.to_account_info()
+            },
+            offer_seeds
+        )
+    )?;
+
+    emit!(OfferClosed {
+        asset_id: offer.asset_id,
+        buyer: offer.buyer,
+        refunded,
+        expired,
+    });
+
+    Ok(())
+}
\ No newline at end of file
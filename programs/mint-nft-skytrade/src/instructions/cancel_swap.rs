@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::SwapCancelled;
+use crate::state::SwapOffer;
+
+#[derive(Accounts)]
+pub struct CancelSwap<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = merkle_tree,
+        seeds = [SwapOffer::SEED.as_bytes(), swap_offer.asset_id.as_ref()],
+        bump = swap_offer.bump
+    )]
+    pub swap_offer: Box<Account<'info, SwapOffer>>,
+
+    #[account(
+        mut,
+        seeds = [constants::SWAP_ESCROW, swap_offer.key().as_ref()],
+        bump
+    )]
+    pub swap_escrow: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::authority = maker
+    )]
+    pub maker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+/// Pass no root when the maker no longer holds the leaf; the escrowed
+/// top-up is still returned and the stale delegate can no longer sign
+/// once the swap account is closed.
+pub fn cancel_swap<'info>(ctx: Context<'_, '_, '_, 'info, CancelSwap<'info>>, root: Option<[u8; 32]>) -> Result<()> {
+    let swap_offer = &ctx.accounts.swap_offer;
+    let swap_seeds: &[&[&[u8]]] = &[&[
+        SwapOffer::SEED.as_bytes(),
+        swap_offer.asset_id.as_ref(),
+        &[swap_offer.bump]
+    ]];
+
+    if let Some(top_up) = swap_offer.maker_top_up() {
+        let swap_escrow = ctx.accounts.swap_escrow.as_ref().ok_or(Errors::MissingSwapAccount)?;
+        let maker_token_account = ctx.accounts.maker_token_account.as_ref().ok_or(Errors::MissingSwapAccount)?;
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: swap_escrow.to_account_info(),
+                    to: maker_token_account.to_account_info(),
+                    authority: swap_offer.to_account_info()
+                },
+                swap_seeds
+            ),
+            top_up.amount
+        )?;
+
+        //close escrow account
+        close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: swap_escrow.to_account_info(),
+                    destination: ctx.accounts.maker.to_account_info(),
+                    authority: swap_offer.to_account_info()
+                },
+                swap_seeds
+            )
+        )?;
+    }
+
+    if let Some(root) = root {
+        let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+            .iter()
+            .map(|account| (account, false, false))
+            .collect();
+
+        // hand the delegate role back to the maker
+        DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+            .tree_config(&ctx.accounts.tree_authority.to_account_info())
+            .leaf_owner(&ctx.accounts.maker.to_account_info())
+            .previous_leaf_delegate(&swap_offer.to_account_info())
+            .new_leaf_delegate(&ctx.accounts.maker.to_account_info())
+            .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+            .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+            .compression_program(&ctx.accounts.compression_program.to_account_info())
+            .system_program(&ctx.accounts.system_program.to_account_info())
+            .add_remaining_accounts(&proof_accounts)
+            .root(root)
+            .data_hash(swap_offer.data_hash)
+            .creator_hash(swap_offer.creator_hash)
+            .nonce(swap_offer.nonce)
+            .index(swap_offer.index)
+            .invoke()?;
+    }
+
+    emit!(SwapCancelled {
+        asset_id: swap_offer.asset_id,
+        maker: swap_offer.maker,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::LeaseRentClaimed;
+use crate::state::{Config, Lease};
+use crate::utils::bps_amount;
+
+#[derive(Accounts)]
+pub struct ClaimLeaseRent<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [Lease::SEED.as_bytes(), lease.asset_id.as_ref()],
+        bump = lease.bump
+    )]
+    pub lease: Account<'info, Lease>,
+
+    #[account(
+        mut,
+        seeds = [constants::LEASE_ESCROW, lease.key().as_ref()],
+        bump
+    )]
+    pub lease_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = lease.payment_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, lease.payment_mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn claim_lease_rent(ctx: Context<ClaimLeaseRent>) -> Result<()> {
+    let lease = &mut ctx.accounts.lease;
+    let amount = lease.claimable(Clock::get()?.unix_timestamp)?;
+    if amount == 0 {
+        return Ok(());
+    }
+    lease.claimed = lease.claimed.checked_add(amount).ok_or(Errors::MathOverflow)?;
+    let protocol_fee = bps_amount(amount, ctx.accounts.config.fees.lease_bps)?;
+
+    let lease_seeds: &[&[&[u8]]] = &[&[
+        Lease::SEED.as_bytes(),
+        lease.asset_id.as_ref(),
+        &[lease.bump]
+    ]];
+    for (destination, amount) in [
+        (&ctx.accounts.owner_token_account, amount - protocol_fee),
+        (&ctx.accounts.treasury_vault, protocol_fee),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.lease_escrow.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: lease.to_account_info()
+                },
+                lease_seeds
+            ),
+            amount
+        )?;
+    }
+
+    emit!(LeaseRentClaimed {
+        asset_id: lease.asset_id,
+        owner: lease.owner,
+        amount,
+        protocol_fee,
+    });
+
+    Ok(())
+}
\ No newline at end of file
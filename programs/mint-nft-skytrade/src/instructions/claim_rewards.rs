@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::events::RewardsClaimed;
+use crate::state::{RewardPool, StakeInfo};
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// the depositor, or whoever the deposit was carried over to
+    pub beneficiary: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: checked against the stake info, gets its rent back once a withdrawn entry is paid out
+    pub depositor: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = beneficiary,
+        has_one = depositor,
+        seeds = [StakeInfo::SEED.as_bytes(), stake_info.cnft.as_ref(), stake_info.depositor.as_ref(), stake_info.mint.as_ref()],
+        bump = stake_info.bump
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [constants::REWARD_VAULT, reward_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_pool.reward_mint,
+        token::authority = beneficiary
+    )]
+    pub beneficiary_reward_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let stake_info = &mut ctx.accounts.stake_info;
+    reward_pool.update(Clock::get()?.unix_timestamp)?;
+    reward_pool.sync_stake(stake_info)?;
+
+    // an underfunded pool pays what it holds and owes the rest
+    let amount = stake_info.pending_rewards.min(ctx.accounts.reward_vault.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+    stake_info.pending_rewards -= amount;
+
+    // a withdrawn or burned entry only stayed open for its rewards
+    if !stake_info.is_open() && !stake_info.has_rewards_owed() {
+        stake_info.close(ctx.accounts.depositor.to_account_info())?;
+    }
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.beneficiary_reward_account.to_account_info(),
+                authority: reward_pool.to_account_info()
+            },
+            &[&[
+                RewardPool::SEED.as_bytes(),
+                reward_pool.stake_mint.as_ref(),
+                &[reward_pool.bump]
+            ]]
+        ),
+        amount
+    )?;
+
+    emit!(RewardsClaimed {
+        asset_id: stake_info.cnft,
+        beneficiary: stake_info.beneficiary,
+        reward_mint: reward_pool.reward_mint,
+        amount,
+        still_owed: stake_info.pending_rewards,
+    });
+
+    Ok(())
+}
\ No newline at end of file
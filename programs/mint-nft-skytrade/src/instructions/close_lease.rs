@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::LeaseClosed;
+use crate::state::{Config, Lease, ParcelState};
+use crate::utils::bps_amount;
+
+#[derive(Accounts)]
+pub struct CloseLease<'info> {
+    /// anyone may close a lease once it has run its course
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = lessee,
+        seeds = [Lease::SEED.as_bytes(), lease.asset_id.as_ref()],
+        bump = lease.bump
+    )]
+    pub lease: Account<'info, Lease>,
+
+    #[account(
+        mut,
+        seeds = [constants::LEASE_ESCROW, lease.key().as_ref()],
+        bump
+    )]
+    pub lease_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ParcelState::SEED.as_bytes(), lease.asset_id.as_ref()],
+        bump = parcel_state.bump
+    )]
+    pub parcel_state: Account<'info, ParcelState>,
+
+    #[account(mut)]
+    /// CHECK: checked against the lease
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: checked against the lease
+    pub lessee: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = lease.payment_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = lease.payment_mint,
+        token::authority = lessee
+    )]
+    pub lessee_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, lease.payment_mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn close_lease(ctx: Context<CloseLease>) -> Result<()> {
+    let lease = &ctx.accounts.lease;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= lease.end_time, Errors::LeaseNotEnded);
+
+    let rent = lease.claimable(now)?;
+    let protocol_fee = bps_amount(rent, ctx.accounts.config.fees.lease_bps)?;
+    let paid_to_owner = rent - protocol_fee;
+    let refunded_to_lessee = ctx.accounts.lease_escrow.amount
+        .checked_sub(rent)
+        .ok_or(Errors::MathOverflow)?;
+
+    let lease_seeds: &[&[&[u8]]] = &[&[
+        Lease::SEED.as_bytes(),
+        lease.asset_id.as_ref(),
+        &[lease.bump]
+    ]];
+    for (destination, amount) in [
+        (&ctx.accounts.owner_token_account, paid_to_owner),
+        (&ctx.accounts.lessee_token_account, refunded_to_lessee),
+        (&ctx.accounts.treasury_vault, protocol_fee),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.lease_escrow.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: lease.to_account_info()
+                },
+                lease_seeds
+            ),
+            amount
+        )?;
+    }
+
+    //close escrow account
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.lease_escrow.to_account_info(),
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: lease.to_account_info()
+            },
+            lease_seeds
+        )
+    )?;
+
+    ctx.accounts.parcel_state.lease_end = 0;
+
+    emit!(LeaseClosed {
+        asset_id: lease.asset_id,
+        owner: lease.owner,
+        lessee: lease.lessee,
+        paid_to_owner,
+        refunded_to_lessee,
+        protocol_fee,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use spl_account_compression::program::SplAccountCompression;
+use crate::errors::Errors;
+use crate::events::ListingCancelled;
+use crate::state::{CurrentLeaf, Listing};
+use crate::utils::verify_leaf;
+
+#[derive(Accounts)]
+pub struct CloseStaleListing<'info> {
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: checked against the listing, gets its rent back
+    pub seller: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        has_one = merkle_tree,
+        seeds = [Listing::SEED.as_bytes(), listing.asset_id.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+    /// CHECK: This account is checked in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+}
+
+/// Lets anyone close a listing whose PDA is no longer the leaf delegate,
+/// for example after the parcel was sold through an offer, so the asset
+/// can be listed again.
+pub fn close_stale_listing<'info>(ctx: Context<'_, '_, '_, 'info, CloseStaleListing<'info>>,
+                                  current_leaf: CurrentLeaf) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    require!(current_leaf.owner != listing.seller || current_leaf.delegate != listing.key(), Errors::LeafNotStale);
+    verify_leaf(
+        &ctx.accounts.compression_program.to_account_info(),
+        &ctx.accounts.merkle_tree.to_account_info(),
+        ctx.remaining_accounts,
+        current_leaf.root,
+        &current_leaf.to_leaf_schema(listing.asset_id, listing.nonce),
+        listing.index
+    )?;
+
+    emit!(ListingCancelled {
+        asset_id: listing.asset_id,
+        seller: listing.seller,
+    });
+
+    Ok(())
+}
\ No newline at end of file
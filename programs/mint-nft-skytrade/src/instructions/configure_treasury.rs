@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::TreasuryConfigured;
+use crate::state::{Config, TokenWhitelist, Treasury};
+
+#[derive(Accounts)]
+pub struct ConfigureTreasury<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        seeds = [Treasury::SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        payer = admin,
+        space = Treasury::SIZE
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init_if_needed,
+        seeds = [constants::TREASURY_VAULT, mint.key().as_ref()],
+        bump,
+        payer = admin,
+        token::mint = mint,
+        token::authority = treasury
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Account<'info, TokenWhitelist>,
+    pub mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn configure_treasury(ctx: Context<ConfigureTreasury>, spending_limit: u64, period: i64) -> Result<()> {
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.mint.key()), Errors::TokenNotWhitelisted);
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.mint = ctx.accounts.mint.key();
+    treasury.bump = ctx.bumps.treasury;
+    treasury.set_spending_limit(spending_limit, period)?;
+
+    emit!(TreasuryConfigured {
+        mint: treasury.mint,
+        spending_limit,
+        period,
+    });
+
+    Ok(())
+}
\ No newline at end of file
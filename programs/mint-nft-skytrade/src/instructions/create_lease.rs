@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::types::LeafSchema;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::program::SplAccountCompression;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::LeaseCreated;
+use crate::state::{Lease, LeaseTerms, ParcelState, TokenWhitelist};
+use crate::utils::verify_leaf;
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], nonce: u64)]
+pub struct CreateLease<'info> {
+    /// the current leaf owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub lessee: Signer<'info>,
+
+    /// CHECK: only hashed into the leaf that is verified against the tree
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [Lease::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        payer = owner,
+        space = Lease::SIZE
+    )]
+    pub lease: Box<Account<'info, Lease>>,
+
+    #[account(
+        init,
+        seeds = [constants::LEASE_ESCROW, lease.key().as_ref()],
+        bump,
+        payer = owner,
+        token::mint = payment_mint,
+        token::authority = lease
+    )]
+    pub lease_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [ParcelState::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        payer = owner,
+        space = ParcelState::SIZE
+    )]
+    pub parcel_state: Box<Account<'info, ParcelState>>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Box<Account<'info, TokenWhitelist>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = lessee
+    )]
+    pub lessee_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn create_lease<'info>(ctx: Context<'_, '_, '_, 'info, CreateLease<'info>>,
+                           root: [u8; 32],
+                           data_hash: [u8; 32],
+                           creator_hash: [u8; 32],
+                           nonce: u64,
+                           index: u32,
+                           terms: LeaseTerms) -> Result<()> {
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.payment_mint.key()), Errors::TokenNotWhitelisted);
+    let now = Clock::get()?.unix_timestamp;
+    let deposit = terms.deposit(now)?;
+
+    let asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+
+    // the lease does not move the cNFT, so ownership is proven by
+    // verifying the owner's leaf against the current root
+    let leaf = LeafSchema::V1 {
+        id: asset_id,
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.leaf_delegate.key(),
+        nonce,
+        data_hash,
+        creator_hash,
+    };
+    verify_leaf(
+        &ctx.accounts.compression_program.to_account_info(),
+        &ctx.accounts.merkle_tree.to_account_info(),
+        ctx.remaining_accounts,
+        root,
+        &leaf,
+        index
+    )?;
+
+    let parcel_state = &mut ctx.accounts.parcel_state;
+    require!(!parcel_state.is_leased(now), Errors::LeaseActive);
+    parcel_state.cnft = asset_id;
+    parcel_state.bump = ctx.bumps.parcel_state;
+    parcel_state.lease_end = terms.end_time;
+
+    let lease = &mut ctx.accounts.lease;
+    lease.owner = ctx.accounts.owner.key();
+    lease.lessee = ctx.accounts.lessee.key();
+    lease.asset_id = asset_id;
+    lease.payment_mint = ctx.accounts.payment_mint.key();
+    lease.start_time = terms.start_time;
+    lease.end_time = terms.end_time;
+    lease.rate_per_second = terms.rate_per_second;
+    lease.deposit = deposit;
+    lease.claimed = 0;
+    lease.bump = ctx.bumps.lease;
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.lessee_token_account.to_account_info(),
+                to: ctx.accounts.lease_escrow.to_account_info(),
+                authority: ctx.accounts.lessee.to_account_info()
+            }
+        ),
+        deposit
+    )?;
+
+    emit!(LeaseCreated {
+        asset_id,
+        owner: lease.owner,
+        lessee: lease.lessee,
+        payment_mint: lease.payment_mint,
+        start_time: lease.start_time,
+        end_time: lease.end_time,
+        rate_per_second: lease.rate_per_second,
+        deposit,
+    });
+
+    Ok(())
+}
\ No newline at end of file
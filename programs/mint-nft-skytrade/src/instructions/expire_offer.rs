@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+use crate::instructions::cancel_offer::{refund_offer, CloseOffer};
+
+/// Permissionless: once an offer has expired anyone can return the
+/// escrowed tokens to the buyer.
+pub fn expire_offer(ctx: Context<CloseOffer>) -> Result<()> {
+    require!(ctx.accounts.offer.is_expired(Clock::get()?.unix_timestamp), Errors::OfferNotExpired);
+    refund_offer(ctx, true)
+}
\ No newline at end of file
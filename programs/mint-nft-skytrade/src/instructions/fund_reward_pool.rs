@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::events::RewardPoolFunded;
+use crate::state::{Config, RewardPool};
+
+#[derive(Accounts)]
+pub struct FundRewardPool<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [RewardPool::SEED.as_bytes(), reward_pool.stake_mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [constants::REWARD_VAULT, reward_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_pool.reward_mint,
+        token::authority = admin
+    )]
+    pub admin_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.admin_token_account.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info()
+            }
+        ),
+        amount
+    )?;
+
+    emit!(RewardPoolFunded {
+        stake_mint: ctx.accounts.reward_pool.stake_mint,
+        reward_mint: ctx.accounts.reward_pool.reward_mint,
+        amount,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{CollectionConfig, Config};
+
+#[derive(Accounts)]
+pub struct InitCollection<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_mint.key().as_ref()],
+        bump,
+        payer = admin,
+        space = CollectionConfig::SIZE
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+    pub collection_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn init_collection(ctx: Context<InitCollection>,
+                       max_supply: Option<u64>,
+                       symbol: String,
+                       allowed_uri_schemes: u8) -> Result<()> {
+    let collection_config = &mut ctx.accounts.collection_config;
+    collection_config.authority = ctx.accounts.admin.key();
+    collection_config.collection_mint = ctx.accounts.collection_mint.key();
+    collection_config.bump = ctx.bumps.collection_config;
+    collection_config.set_symbol(symbol)?;
+    collection_config.set_allowed_uri_schemes(allowed_uri_schemes)?;
+    collection_config.set_max_supply(max_supply)
+}
\ No newline at end of file
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::program::MintNftSkytrade;
+use crate::state::Config;
+
+/// Creates the protocol config on its own, so deployments whose whitelist
+/// already exists can add it. Only the upgrade authority may claim admin.
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        seeds = [Config::SEED.as_bytes()],
+        bump,
+        payer = admin,
+        space = Config::SIZE
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program: Program<'info, MintNftSkytrade>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ Errors::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn init_config(ctx: Context<InitConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.treasurer = ctx.accounts.admin.key();
+    config.unlock_cooldown = constants::DEFAULT_UNLOCK_COOLDOWN;
+    config.arbitrator = ctx.accounts.admin.key();
+    config.bump = ctx.bumps.config;
+    Ok(())
+}
\ No newline at end of file
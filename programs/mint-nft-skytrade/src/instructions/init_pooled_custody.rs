@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::state::{Config, PooledCustody, TokenWhitelist};
+
+#[derive(Accounts)]
+pub struct InitPooledCustody<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        seeds = [PooledCustody::SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        payer = admin,
+        space = PooledCustody::SIZE
+    )]
+    pub pooled_custody: Account<'info, PooledCustody>,
+
+    #[account(
+        init,
+        seeds = [constants::POOLED_VAULT, mint.key().as_ref()],
+        bump,
+        payer = admin,
+        token::mint = mint,
+        token::authority = vault_authority
+    )]
+    pub pooled_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [constants::VAULT_AUTHORITY],
+        bump
+    )]
+    /// CHECK: signs for every stake vault, holds no data
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Account<'info, TokenWhitelist>,
+    pub mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn init_pooled_custody(ctx: Context<InitPooledCustody>) -> Result<()> {
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.mint.key()), Errors::TokenNotWhitelisted);
+
+    let pooled_custody = &mut ctx.accounts.pooled_custody;
+    pooled_custody.mint = ctx.accounts.mint.key();
+    pooled_custody.bump = ctx.bumps.pooled_custody;
+    Ok(())
+}
\ No newline at end of file
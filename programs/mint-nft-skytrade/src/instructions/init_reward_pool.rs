@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::RewardPoolUpdated;
+use crate::state::{Config, RewardPool, TokenWhitelist};
+
+#[derive(Accounts)]
+pub struct InitRewardPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        seeds = [RewardPool::SEED.as_bytes(), stake_mint.key().as_ref()],
+        bump,
+        payer = admin,
+        space = RewardPool::SIZE
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        seeds = [constants::REWARD_VAULT, reward_pool.key().as_ref()],
+        bump,
+        payer = admin,
+        token::mint = reward_mint,
+        token::authority = reward_pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Account<'info, TokenWhitelist>,
+    pub stake_mint: Account<'info, Mint>,
+    pub reward_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn init_reward_pool(ctx: Context<InitRewardPool>, emission_rate: u64) -> Result<()> {
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.stake_mint.key()), Errors::TokenNotWhitelisted);
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.stake_mint = ctx.accounts.stake_mint.key();
+    reward_pool.reward_mint = ctx.accounts.reward_mint.key();
+    reward_pool.emission_rate = emission_rate;
+    reward_pool.last_update = Clock::get()?.unix_timestamp;
+    reward_pool.bump = ctx.bumps.reward_pool;
+
+    emit!(RewardPoolUpdated {
+        stake_mint: reward_pool.stake_mint,
+        reward_mint: reward_pool.reward_mint,
+        emission_rate,
+    });
+
+    Ok(())
+}
\ No newline at end of file
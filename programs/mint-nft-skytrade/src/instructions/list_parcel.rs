@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::errors::Errors;
+use crate::events::ParcelListed;
+use crate::state::{Auction, CollectionConfig, DutchPricing, Listing, ParcelMetadata, ParcelState, TokenWhitelist};
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], nonce: u64)]
+pub struct ListParcel<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [Listing::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        payer = seller,
+        space = Listing::SIZE
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [Auction::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump
+    )]
+    /// CHECK: must not be initialized, the parcel can't be listed while it is auctioned
+    pub auction: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Box<Account<'info, TokenWhitelist>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: This account is checked in the downstream program
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn list_parcel<'info>(ctx: Context<'_, '_, '_, 'info, ListParcel<'info>>,
+                          root: [u8; 32],
+                          nonce: u64,
+                          index: u32,
+                          metadata: ParcelMetadata,
+                          price: u64) -> Result<()> {
+    require!(price > 0, Errors::InvalidPrice);
+    create_listing(ctx, root, nonce, index, metadata, price, None)
+}
+
+/// Delegates the leaf to the listing PDA and records the sale terms.
+/// Shared by fixed-price and dutch listings.
+pub(crate) fn create_listing<'info>(ctx: Context<'_, '_, '_, 'info, ListParcel<'info>>,
+                                    root: [u8; 32],
+                                    nonce: u64,
+                                    index: u32,
+                                    metadata: ParcelMetadata,
+                                    price: u64,
+                                    dutch: Option<DutchPricing>) -> Result<()> {
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.payment_mint.key()), Errors::TokenNotWhitelisted);
+    ParcelState::ensure_transferable(&ctx.accounts.parcel_state)?;
+    // cancel_auction or settle_auction close the auction first
+    require!(ctx.accounts.auction.data_is_empty(), Errors::ParcelOnSale);
+
+    // the delegate cpi verifies these hashes against the tree, which
+    // proves the metadata (and so the royalty) supplied by the seller
+    let collection_mint = ctx.accounts.collection_config.collection_mint;
+    let data_hash = hash_metadata(&metadata.to_metadata_args(collection_mint))
+        .map_err(|_| Errors::InvalidMetadata)?;
+    let creator_hash = hash_creators(&[]);
+
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.seller.to_account_info())
+        .previous_leaf_delegate(&ctx.accounts.previous_leaf_delegate.to_account_info())
+        .new_leaf_delegate(&ctx.accounts.listing.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .invoke()?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.seller = ctx.accounts.seller.key();
+    listing.asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+    listing.merkle_tree = ctx.accounts.merkle_tree.key();
+    listing.nonce = nonce;
+    listing.index = index;
+    listing.data_hash = data_hash;
+    listing.creator_hash = creator_hash;
+    listing.collection_mint = collection_mint;
+    listing.royalty_bps = metadata.seller_fee_basis_points;
+    listing.payment_mint = ctx.accounts.payment_mint.key();
+    listing.price = price;
+    listing.dutch = dutch;
+    listing.created_at = Clock::get()?.unix_timestamp;
+    listing.bump = ctx.bumps.listing;
+
+    emit!(ParcelListed {
+        asset_id: listing.asset_id,
+        seller: listing.seller,
+        payment_mint: listing.payment_mint,
+        price,
+    });
+
+    Ok(())
+}
\ No newline at end of file
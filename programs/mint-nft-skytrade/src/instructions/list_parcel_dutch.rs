@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use crate::instructions::list_parcel::{create_listing, ListParcel};
+use crate::state::{DutchPricing, ParcelMetadata};
+
+pub fn list_parcel_dutch<'info>(ctx: Context<'_, '_, '_, 'info, ListParcel<'info>>,
+                                root: [u8; 32],
+                                nonce: u64,
+                                index: u32,
+                                metadata: ParcelMetadata,
+                                pricing: DutchPricing) -> Result<()> {
+    pricing.validate()?;
+    create_listing(ctx, root, nonce, index, metadata, pricing.start_price, Some(pricing))
+}
\ No newline at end of file
@@ -1,19 +1,29 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::types::LeafSchema;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::program::SplAccountCompression;
 use crate::constants::*;
 use crate::errors::Errors;
-use crate::state::{Asset, TokenWhitelist};
+use crate::events::DepositSponsored;
+use crate::state::{Asset, Config, ParcelState, PooledCustody, RewardPool, Sponsorship, StakeInfo, TokenWhitelist};
+use crate::utils::{bps_amount, verify_leaf};
 
 #[derive(Accounts)]
+#[instruction(root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], nonce: u64)]
 pub struct LockFund<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    /// CHECK: should be vetted from front end
+    /// CHECK: only hashed into the leaf that is verified against the tree
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: the asset id of the leaf the funds are locked against
+    #[account(address = get_asset_id(&merkle_tree.key(), nonce))]
     pub cnft: UncheckedAccount<'info>,
 
-    /// CHECK: This account is modified in the downstream program
-    pub asset_info: AccountInfo<'info>,
+    /// CHECK: checked against the cnft when the valuation is loaded
+    pub asset_info: UncheckedAccount<'info>,
 
     #[account(
         mut
@@ -22,13 +32,60 @@ pub struct LockFund<'info> {
 
     #[account(
         init_if_needed,
-        seeds = [constants::STAKE_VAULT, cnft.key.as_ref()],
+        seeds = [constants::STAKE_VAULT, cnft.key.as_ref(), tx_token_mint.key().as_ref()],
         bump,
         payer = signer,
         token::mint = tx_token_mint,
-        token::authority = cnft_stake_vault
+        token::authority = vault_authority
+    )]
+    pub cnft_stake_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [PooledCustody::SEED.as_bytes(), tx_token_mint.key().as_ref()],
+        bump = pooled_custody.bump
+    )]
+    pub pooled_custody: Option<Box<Account<'info, PooledCustody>>>,
+
+    #[account(
+        mut,
+        seeds = [constants::POOLED_VAULT, tx_token_mint.key().as_ref()],
+        bump
+    )]
+    pub pooled_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [constants::VAULT_AUTHORITY],
+        bump
     )]
-    pub cnft_stake_vault: Account<'info, TokenAccount>,
+    /// CHECK: signs for every stake vault, holds no data
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [ParcelState::SEED.as_bytes(), cnft.key.as_ref()],
+        bump,
+        payer = signer,
+        space = ParcelState::SIZE
+    )]
+    pub parcel_state: Account<'info, ParcelState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [StakeInfo::SEED.as_bytes(), cnft.key.as_ref(), signer.key.as_ref(), tx_token_mint.key().as_ref()],
+        bump,
+        payer = signer,
+        space = StakeInfo::SIZE
+    )]
+    pub stake_info: Box<Account<'info, StakeInfo>>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), tx_token_mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized for mints with a reward pool
+    pub reward_pool: UncheckedAccount<'info>,
 
     #[account(
         seeds = [TokenWhitelist::SEED.as_bytes()],
@@ -36,23 +93,136 @@ pub struct LockFund<'info> {
     )]
     pub whitelist: Account<'info, TokenWhitelist>,
 
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, tx_token_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
     pub tx_token_mint: Account<'info, Mint>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub compression_program: Program<'info, SplAccountCompression>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>
 }
 
-pub fn lock_fund(ctx: Context<LockFund>) -> Result<()> {
-    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.tx_token_mint.key()), Errors::TokenAlreadyWhitelisted);
-    let asset = Asset::from_account_info(&ctx.accounts.asset_info);
+pub fn lock_fund<'info>(ctx: Context<'_, '_, '_, 'info, LockFund<'info>>,
+                        root: [u8; 32],
+                        data_hash: [u8; 32],
+                        creator_hash: [u8; 32],
+                        nonce: u64,
+                        index: u32,
+                        sponsorship: Option<Sponsorship>) -> Result<()> {
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.tx_token_mint.key()), Errors::TokenNotWhitelisted);
+
+    // only the leaf owner, or a sponsor naming them, can pin the parcel
+    let owner = sponsorship.map_or(ctx.accounts.signer.key(), |sponsorship| sponsorship.owner);
+    let leaf = LeafSchema::V1 {
+        id: ctx.accounts.cnft.key(),
+        owner,
+        delegate: ctx.accounts.leaf_delegate.key(),
+        nonce,
+        data_hash,
+        creator_hash,
+    };
+    verify_leaf(
+        &ctx.accounts.compression_program.to_account_info(),
+        &ctx.accounts.merkle_tree.to_account_info(),
+        ctx.remaining_accounts,
+        root,
+        &leaf,
+        index
+    )?;
+    let asset = Asset::load(&ctx.accounts.asset_info, ctx.accounts.cnft.key())?;
+
+    // passing the pooled custody accounts opts the deposit into the mint's
+    // pooled vault instead of a vault of its own
+    let (vault, pooled) = match (
+        &ctx.accounts.cnft_stake_vault,
+        &ctx.accounts.pooled_custody,
+        &ctx.accounts.pooled_vault
+    ) {
+        (Some(vault), None, None) => (vault.to_account_info(), false),
+        (None, Some(_), Some(vault)) => (vault.to_account_info(), true),
+        _ => return err!(Errors::CustodyMismatch)
+    };
+
+    // lock_fund opens a deposit; top_up_lock adds to an open one
+    let stake_info = &mut ctx.accounts.stake_info;
+    require!(!stake_info.is_open(), Errors::AlreadyLocked);
+    stake_info.cnft = ctx.accounts.cnft.key();
+    stake_info.set_sponsorship(ctx.accounts.signer.key(), sponsorship);
+    stake_info.mint = ctx.accounts.tx_token_mint.key();
+    stake_info.pooled = pooled;
+    stake_info.bump = ctx.bumps.stake_info;
+    let now = Clock::get()?.unix_timestamp;
+    stake_info.record_open(now);
+    stake_info.record_deposit(asset.price, asset.price, ctx.accounts.asset_info.key())?;
+    if let Some(mut reward_pool) = RewardPool::load(&ctx.accounts.reward_pool)? {
+        reward_pool.update(now)?;
+        reward_pool.sync_stake(stake_info)?;
+        reward_pool.save(&ctx.accounts.reward_pool)?;
+    }
+
+    let parcel_state = &mut ctx.accounts.parcel_state;
+    parcel_state.cnft = ctx.accounts.cnft.key();
+    parcel_state.bump = ctx.bumps.parcel_state;
+    parcel_state.record_lock(asset.price, true)?;
+
+    // the lock fee is charged on top of the locked amount
+    let lock_fee = bps_amount(asset.price, ctx.accounts.config.fees.lock_bps)?;
+    if lock_fee > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.signer_token_ata.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info()
+                }
+            ),
+            lock_fee
+        )?;
+    }
+
     transfer(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
                 from: ctx.accounts.signer_token_ata.to_account_info(),
-                to: ctx.accounts.cnft_stake_vault.to_account_info(),
+                to: vault,
                 authority: ctx.accounts.signer.to_account_info()
             }
         ),
         asset.price
-    )
+    )?;
+
+    if let (Some(pooled_custody), Some(pooled_vault)) =
+        (&mut ctx.accounts.pooled_custody, &mut ctx.accounts.pooled_vault) {
+        pooled_custody.record_deposit(asset.price, true)?;
+        pooled_vault.reload()?;
+        pooled_custody.reconcile(pooled_vault.amount)?;
+    }
+
+    let stake_info = &ctx.accounts.stake_info;
+    if sponsorship.is_some() {
+        emit!(DepositSponsored {
+            asset_id: stake_info.cnft,
+            sponsor: stake_info.depositor,
+            owner: stake_info.owner,
+            beneficiary: stake_info.beneficiary,
+            amount: asset.price,
+        });
+    }
+
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::OfferMade;
+use crate::state::{Offer, TokenWhitelist};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [Offer::SEED.as_bytes(), asset_id.as_ref(), buyer.key().as_ref()],
+        bump,
+        payer = buyer,
+        space = Offer::SIZE
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        seeds = [constants::OFFER_ESCROW, offer.key().as_ref()],
+        bump,
+        payer = buyer,
+        token::mint = payment_mint,
+        token::authority = offer
+    )]
+    pub offer_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Account<'info, TokenWhitelist>,
+    pub payment_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn make_offer(ctx: Context<MakeOffer>, asset_id: Pubkey, amount: u64, expires_at: i64) -> Result<()> {
+    require!(amount > 0, Errors::InvalidPrice);
+    require!(expires_at > Clock::get()?.unix_timestamp, Errors::InvalidExpiry);
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.payment_mint.key()), Errors::TokenNotWhitelisted);
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.buyer_token_account.to_account_info(),
+                to: ctx.accounts.offer_escrow.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info()
+            }
+        ),
+        amount
+    )?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.buyer = ctx.accounts.buyer.key();
+    offer.asset_id = asset_id;
+    offer.payment_mint = ctx.accounts.payment_mint.key();
+    offer.amount = amount;
+    offer.expires_at = expires_at;
+    offer.bump = ctx.bumps.offer;
+
+    emit!(OfferMade {
+        asset_id,
+        buyer: offer.buyer,
+        payment_mint: offer.payment_mint,
+        amount,
+        expires_at,
+    });
+
+    Ok(())
+}
\ No newline at end of file
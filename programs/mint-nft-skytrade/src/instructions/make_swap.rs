@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::SwapMade;
+use crate::state::{ParcelState, SwapOffer, SwapTerms, TokenWhitelist};
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], nonce: u64)]
+pub struct MakeSwap<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [SwapOffer::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        payer = maker,
+        space = SwapOffer::SIZE
+    )]
+    pub swap_offer: Box<Account<'info, SwapOffer>>,
+
+    /// only needed when the maker pays a top-up
+    #[account(
+        init,
+        seeds = [constants::SWAP_ESCROW, swap_offer.key().as_ref()],
+        bump,
+        payer = maker,
+        token::mint = payment_mint,
+        token::authority = swap_offer
+    )]
+    pub swap_escrow: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = maker
+    )]
+    pub maker_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub payment_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Box<Account<'info, TokenWhitelist>>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: This account is checked in the downstream program
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn make_swap<'info>(ctx: Context<'_, '_, '_, 'info, MakeSwap<'info>>,
+                        root: [u8; 32],
+                        data_hash: [u8; 32],
+                        creator_hash: [u8; 32],
+                        nonce: u64,
+                        index: u32,
+                        terms: SwapTerms) -> Result<()> {
+    terms.validate(ctx.accounts.maker.key(), Clock::get()?.unix_timestamp)?;
+    ParcelState::ensure_transferable(&ctx.accounts.parcel_state)?;
+
+    if let Some(top_up) = terms.top_up {
+        let payment_mint = ctx.accounts.payment_mint.as_ref().ok_or(Errors::MissingSwapAccount)?;
+        require_keys_eq!(payment_mint.key(), top_up.payment_mint, Errors::InvalidSwapTerms);
+        require!(ctx.accounts.whitelist.tokens.contains(&top_up.payment_mint), Errors::TokenNotWhitelisted);
+
+        if top_up.paid_by_maker {
+            let swap_escrow = ctx.accounts.swap_escrow.as_ref().ok_or(Errors::MissingSwapAccount)?;
+            let maker_token_account = ctx.accounts.maker_token_account.as_ref().ok_or(Errors::MissingSwapAccount)?;
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: maker_token_account.to_account_info(),
+                        to: swap_escrow.to_account_info(),
+                        authority: ctx.accounts.maker.to_account_info()
+                    }
+                ),
+                top_up.amount
+            )?;
+        }
+    }
+
+    // the delegate cpi proves the maker owns the leaf
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.maker.to_account_info())
+        .previous_leaf_delegate(&ctx.accounts.previous_leaf_delegate.to_account_info())
+        .new_leaf_delegate(&ctx.accounts.swap_offer.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .invoke()?;
+
+    let swap_offer = &mut ctx.accounts.swap_offer;
+    swap_offer.maker = ctx.accounts.maker.key();
+    swap_offer.taker = terms.taker;
+    swap_offer.asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+    swap_offer.merkle_tree = ctx.accounts.merkle_tree.key();
+    swap_offer.nonce = nonce;
+    swap_offer.index = index;
+    swap_offer.data_hash = data_hash;
+    swap_offer.creator_hash = creator_hash;
+    swap_offer.requested_asset = terms.requested_asset;
+    swap_offer.top_up = terms.top_up;
+    swap_offer.expires_at = terms.expires_at;
+    swap_offer.bump = ctx.bumps.swap_offer;
+
+    emit!(SwapMade {
+        asset_id: swap_offer.asset_id,
+        maker: swap_offer.maker,
+        taker: swap_offer.taker,
+        requested_asset: terms.requested_asset,
+        top_up_mint: terms.top_up.map(|top_up| top_up.payment_mint),
+        top_up_amount: terms.top_up.map_or(0, |top_up| top_up.amount),
+        top_up_paid_by_maker: terms.top_up.is_some_and(|top_up| top_up.paid_by_maker),
+        expires_at: terms.expires_at,
+    });
+
+    Ok(())
+}
\ No newline at end of file
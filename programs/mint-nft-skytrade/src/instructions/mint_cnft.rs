@@ -1,18 +1,41 @@
 use anchor_lang::prelude::*;
+use mpl_bubblegum::accounts::TreeConfig;
 use mpl_bubblegum::instructions::MintToCollectionV1CpiBuilder;
-use mpl_bubblegum::types::{Collection, MetadataArgs, TokenProgramVersion, TokenStandard};
+use mpl_bubblegum::utils::get_asset_id;
 use spl_account_compression::{program::SplAccountCompression, Noop};
 use crate::{MplBubblegum};
+use crate::state::{Asset, CollectionConfig, Config, MintReceipt, ParcelMetadata};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::ParcelMinted;
+use crate::utils::bps_amount;
 use anchor_spl::{
-    metadata::{Metadata}
+    metadata::{Metadata},
+    token::{Token, TokenAccount, transfer, Transfer}
 };
 
 
+/// Identifies the leaf created by `mint_cnft`, returned to the caller through
+/// `set_return_data` so clients do not need an indexer to find the asset id.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MintedParcel {
+    pub asset_id: Pubkey,
+    pub leaf_index: u32,
+}
 
 #[derive(Accounts)]
+#[instruction(request_id: [u8; 32])]
 pub struct MintCNFT<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [MintReceipt::SEED.as_bytes(), request_id.as_ref()],
+        bump,
+        payer = payer,
+        space = MintReceipt::SIZE
+    )]
+    pub mint_receipt: Account<'info, MintReceipt>,
     /// CHECK: This account is checked in the instruction
     #[account(mut)]
     pub tree_config: UncheckedAccount<'info>,
@@ -31,6 +54,30 @@ pub struct MintCNFT<'info> {
     pub central_authority: UncheckedAccount<'info>,
     /// CHECK: This account is checked in the instruction
     pub collection_mint: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // the mint fee accounts are only needed while the mint fee is non-zero
+    /// CHECK: checked against the asset being minted when the valuation is loaded
+    pub asset_info: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        token::authority = payer
+    )]
+    pub payer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(mut)]
+    pub treasury_vault: Option<Box<Account<'info, TokenAccount>>>,
+    pub token_program: Option<Program<'info, Token>>,
+
     /// CHECK:
     #[account(mut)]
     pub collection_metadata: UncheckedAccount<'info>,
@@ -47,12 +94,53 @@ pub struct MintCNFT<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn mint_cnft(ctx: Context<MintCNFT>, name: String,symbol: String, uri: String,seller_fee_basis_points: u16) -> Result<()> {
+pub fn mint_cnft(ctx: Context<MintCNFT>, request_id: [u8; 32], name: String,symbol: String, uri: String,seller_fee_basis_points: u16) -> Result<MintedParcel> {
     msg!("<<<<<minting nft");
     // require!(ctx.accounts.central_authority.merkle_tree_address.is_some(), MyError::InvalidMerkleTree);
     // require_keys_eq!(*ctx.accounts.merkle_tree.key, ctx.accounts.central_authority.merkle_tree_address.unwrap(), MyError::InvalidMerkleTree);
     // require_keys_eq!(*ctx.accounts.collection_mint.key, ctx.accounts.central_authority.collection_address, MyError::InvalidMerkleTree);
-  
+    // a retried request finds its receipt already written and is rejected
+    require!(!ctx.accounts.mint_receipt.is_processed(), Errors::DuplicateMintRequest);
+    ctx.accounts.collection_config.validate_metadata(&name, &symbol, &uri, seller_fee_basis_points)?;
+    ctx.accounts.collection_config.record_mint()?;
+
+    // bubblegum appends the new leaf at `num_minted`, which is also its nonce
+    let tree_config = TreeConfig::from_bytes(&ctx.accounts.tree_config.try_borrow_data()?)
+        .map_err(|_| Errors::InvalidTreeConfig)?;
+    let nonce = tree_config.num_minted;
+    let leaf_index = u32::try_from(nonce).map_err(|_| Errors::MathOverflow)?;
+    let asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+
+    let mint_fee_bps = ctx.accounts.config.fees.mint_bps;
+    if mint_fee_bps > 0 {
+        let (Some(asset_info), Some(payer_token_account), Some(treasury_vault), Some(token_program)) = (
+            ctx.accounts.asset_info.as_ref(),
+            ctx.accounts.payer_token_account.as_ref(),
+            ctx.accounts.treasury_vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        ) else {
+            return err!(Errors::InvalidFeeAccount);
+        };
+        let (treasury_vault_key, _) = Pubkey::find_program_address(
+            &[constants::TREASURY_VAULT, payer_token_account.mint.as_ref()],
+            ctx.program_id
+        );
+        require_keys_eq!(treasury_vault.key(), treasury_vault_key, Errors::InvalidFeeAccount);
+
+        let mint_fee = bps_amount(Asset::load(asset_info, asset_id)?.price, mint_fee_bps)?;
+        transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                Transfer {
+                    from: payer_token_account.to_account_info(),
+                    to: treasury_vault.to_account_info(),
+                    authority: ctx.accounts.payer.to_account_info()
+                }
+            ),
+            mint_fee
+        )?;
+    }
+
     MintToCollectionV1CpiBuilder::new(
         &ctx.accounts.bubblegum_program.to_account_info(),
     )
@@ -73,28 +161,37 @@ pub fn mint_cnft(ctx: Context<MintCNFT>, name: String,symbol: String, uri: Strin
         .token_metadata_program(&ctx.accounts.token_metadata_program.to_account_info())
         .system_program(&ctx.accounts.system_program.to_account_info())
         .metadata(
-            MetadataArgs {
+            ParcelMetadata {
                 name,
                 symbol,
                 uri,
-                creators: vec![],
                 seller_fee_basis_points,
                 primary_sale_happened: false,
-                is_mutable: false,
-                edition_nonce: Some(0),
-                uses: None,
-                collection: Some(Collection {
-                    verified: true,
-                    key: ctx.accounts.collection_mint.key(),
-                }),
-                token_program_version: TokenProgramVersion::Original,
-                token_standard: Some(TokenStandard::NonFungible),
-            }
+                is_mutable: true,
+            }.to_metadata_args(ctx.accounts.collection_mint.key())
         )
         .invoke_signed(&[&[
             b"tree_owner",
             ctx.accounts.merkle_tree.key().as_ref(),
             &[ctx.bumps.central_authority]
         ]])?;
-    Ok(())
+
+    let mint_receipt = &mut ctx.accounts.mint_receipt;
+    mint_receipt.request_id = request_id;
+    mint_receipt.asset_id = asset_id;
+    mint_receipt.leaf_index = leaf_index;
+    mint_receipt.merkle_tree = ctx.accounts.merkle_tree.key();
+    mint_receipt.leaf_owner = ctx.accounts.leaf_owner.key();
+    mint_receipt.minted_at = Clock::get()?.unix_timestamp;
+    mint_receipt.bump = ctx.bumps.mint_receipt;
+
+    emit!(ParcelMinted {
+        asset_id,
+        leaf_index,
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+        leaf_owner: ctx.accounts.leaf_owner.key(),
+        collection_mint: ctx.accounts.collection_mint.key(),
+    });
+
+    Ok(MintedParcel { asset_id, leaf_index })
 }
\ No newline at end of file
@@ -6,6 +6,50 @@ mod whitelist_token;
 mod delist_token;
 mod init;
 mod transfer;
+mod init_collection;
+mod update_collection;
+mod update_parcel_metadata;
+mod delegate_cnft;
+mod revoke_cnft_delegate;
+mod update_config;
+mod list_parcel;
+mod update_listing;
+mod cancel_listing;
+mod buy_parcel;
+mod start_auction;
+mod place_bid;
+mod settle_auction;
+mod withdraw_bid;
+mod cancel_auction;
+mod withdraw_legacy_vault;
+mod list_parcel_dutch;
+mod make_offer;
+mod cancel_offer;
+mod expire_offer;
+mod accept_offer;
+mod create_lease;
+mod claim_lease_rent;
+mod close_lease;
+mod make_swap;
+mod cancel_swap;
+mod accept_swap;
+mod configure_treasury;
+mod withdraw_treasury;
+mod request_unlock;
+mod withdraw_unlocked;
+mod init_reward_pool;
+mod update_reward_pool;
+mod fund_reward_pool;
+mod claim_rewards;
+mod init_pooled_custody;
+mod reconcile_pooled_custody;
+mod top_up_lock;
+mod partial_unlock;
+mod update_penalty_policy;
+mod slash_deposit;
+mod init_config;
+mod close_stale_listing;
+mod value_asset;
 
 pub use create_tree::*;
 pub use mint_cnft::*;
@@ -14,4 +58,48 @@ pub use lock_fund::*;
 pub use whitelist_token::*;
 pub use delist_token::*;
 pub use init::*;
-pub use transfer::*;
\ No newline at end of file
+pub use transfer::*;
+pub use init_collection::*;
+pub use update_collection::*;
+pub use update_parcel_metadata::*;
+pub use delegate_cnft::*;
+pub use revoke_cnft_delegate::*;
+pub use update_config::*;
+pub use list_parcel::*;
+pub use update_listing::*;
+pub use cancel_listing::*;
+pub use buy_parcel::*;
+pub use start_auction::*;
+pub use place_bid::*;
+pub use settle_auction::*;
+pub use withdraw_bid::*;
+pub use cancel_auction::*;
+pub use withdraw_legacy_vault::*;
+pub use list_parcel_dutch::*;
+pub use make_offer::*;
+pub use cancel_offer::*;
+pub use expire_offer::*;
+pub use accept_offer::*;
+pub use create_lease::*;
+pub use claim_lease_rent::*;
+pub use close_lease::*;
+pub use make_swap::*;
+pub use cancel_swap::*;
+pub use accept_swap::*;
+pub use configure_treasury::*;
+pub use withdraw_treasury::*;
+pub use request_unlock::*;
+pub use withdraw_unlocked::*;
+pub use init_reward_pool::*;
+pub use update_reward_pool::*;
+pub use fund_reward_pool::*;
+pub use claim_rewards::*;
+pub use init_pooled_custody::*;
+pub use reconcile_pooled_custody::*;
+pub use top_up_lock::*;
+pub use partial_unlock::*;
+pub use update_penalty_policy::*;
+pub use slash_deposit::*;
+pub use init_config::*;
+pub use close_stale_listing::*;
+pub use value_asset::*;
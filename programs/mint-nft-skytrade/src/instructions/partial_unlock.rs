@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::{LockPartiallyUnlocked, PenaltyRecorded};
+use crate::state::{Asset, Config, ParcelState, PenaltyKind, PenaltyRecord, PooledCustody, RewardPool, StakeInfo};
+
+#[derive(Accounts)]
+pub struct PartialUnlock<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = beneficiary,
+        seeds = [StakeInfo::SEED.as_bytes(), stake_info.cnft.as_ref(), stake_info.depositor.as_ref(), stake_info.mint.as_ref()],
+        bump = stake_info.bump
+    )]
+    pub stake_info: Box<Account<'info, StakeInfo>>,
+
+    #[account(
+        address = stake_info.asset
+    )]
+    /// CHECK: the asset the deposit was priced against
+    pub asset_info: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ParcelState::SEED.as_bytes(), stake_info.cnft.as_ref()],
+        bump = parcel_state.bump
+    )]
+    pub parcel_state: Account<'info, ParcelState>,
+
+    #[account(
+        mut,
+        seeds = [constants::STAKE_VAULT, stake_info.cnft.as_ref(), stake_info.mint.as_ref()],
+        bump
+    )]
+    pub cnft_stake_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [PooledCustody::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump = pooled_custody.bump
+    )]
+    pub pooled_custody: Option<Box<Account<'info, PooledCustody>>>,
+
+    #[account(
+        mut,
+        seeds = [constants::POOLED_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub pooled_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [constants::VAULT_AUTHORITY],
+        bump
+    )]
+    /// CHECK: signs for every stake vault, holds no data
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized for mints with a reward pool
+    pub reward_pool: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = stake_info.mint,
+        token::authority = beneficiary
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
+    /// only needed when the unlock comes before the minimum lock duration
+    #[account(
+        init,
+        seeds = [PenaltyRecord::SEED.as_bytes(), config.penalties.to_le_bytes().as_ref()],
+        bump,
+        payer = beneficiary,
+        space = PenaltyRecord::SIZE
+    )]
+    pub penalty_record: Option<Box<Account<'info, PenaltyRecord>>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn partial_unlock(ctx: Context<PartialUnlock>, amount: u64) -> Result<()> {
+    let asset = Asset::load(&ctx.accounts.asset_info, ctx.accounts.stake_info.cnft)?;
+    let now = Clock::get()?.unix_timestamp;
+    let stake_info = &mut ctx.accounts.stake_info;
+    // a partial unlock waits out the same cooldown as a full one
+    stake_info.check_unlock(now)?;
+    stake_info.record_partial_unlock(amount)?;
+    // only this deposit's excess over the asset's current price can be taken out
+    stake_info.check_minimum_deposit(asset.price)?;
+    if let Some(mut reward_pool) = RewardPool::load(&ctx.accounts.reward_pool)? {
+        reward_pool.update(now)?;
+        reward_pool.sync_stake(stake_info)?;
+        reward_pool.save(&ctx.accounts.reward_pool)?;
+    }
+
+    ctx.accounts.parcel_state.record_unlock(amount, false)?;
+
+    let vault = match (stake_info.pooled, &ctx.accounts.cnft_stake_vault, &ctx.accounts.pooled_vault) {
+        (false, Some(vault), _) | (true, _, Some(vault)) => vault,
+        _ => return err!(Errors::CustodyMismatch)
+    };
+    let vault_seeds: &[&[&[u8]]] = &[&[constants::VAULT_AUTHORITY, &[ctx.bumps.vault_authority]]];
+
+    // unlocking before the minimum lock duration forfeits part of the amount
+    let penalty = ctx.accounts.config.early_unlock_penalty(amount, stake_info.locked_at, now)?;
+    require!((penalty > 0) == ctx.accounts.penalty_record.is_some(), Errors::PenaltyRecordMismatch);
+    if penalty > 0 {
+        let index = ctx.accounts.config.next_penalty()?;
+        let penalty_record = ctx.accounts.penalty_record.as_mut().ok_or(Errors::PenaltyRecordMismatch)?;
+        penalty_record.bump = ctx.bumps.penalty_record;
+        penalty_record.record(PenaltyKind::EarlyUnlock, stake_info, penalty, constants::EARLY_UNLOCK_REASON,
+            ctx.accounts.beneficiary.key())?;
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info()
+                },
+                vault_seeds
+            ),
+            penalty
+        )?;
+
+        emit!(PenaltyRecorded {
+            index,
+            kind: PenaltyKind::EarlyUnlock,
+            asset_id: stake_info.cnft,
+            depositor: stake_info.depositor,
+            mint: stake_info.mint,
+            amount: penalty,
+            reason_code: constants::EARLY_UNLOCK_REASON,
+            authority: penalty_record.authority,
+        });
+    }
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info()
+            },
+            vault_seeds
+        ),
+        amount - penalty
+    )?;
+
+    if stake_info.pooled {
+        let pooled_custody = ctx.accounts.pooled_custody.as_mut().ok_or(Errors::CustodyMismatch)?;
+        pooled_custody.record_withdrawal(amount, false)?;
+        pooled_custody.reconcile(vault.amount.checked_sub(amount).ok_or(Errors::MathOverflow)?)?;
+    }
+
+    emit!(LockPartiallyUnlocked {
+        asset_id: stake_info.cnft,
+        beneficiary: stake_info.beneficiary,
+        amount: amount - penalty,
+        penalty,
+        remaining: stake_info.amount,
+    });
+
+    Ok(())
+}
\ No newline at end of file
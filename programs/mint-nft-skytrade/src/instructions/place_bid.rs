@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::BidPlaced;
+use crate::state::{Auction, Bid};
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = payment_mint,
+        seeds = [Auction::SEED.as_bytes(), auction.asset_id.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [Bid::SEED.as_bytes(), auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        payer = bidder,
+        space = Bid::SIZE
+    )]
+    pub bid: Box<Account<'info, Bid>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [constants::BID_ESCROW, bid.key().as_ref()],
+        bump,
+        payer = bidder,
+        token::mint = payment_mint,
+        token::authority = bid
+    )]
+    pub bid_escrow: Box<Account<'info, TokenAccount>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = bidder
+    )]
+    pub bidder_token_account: Box<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+/// Raises the bidder's standing bid to `amount`, escrowing only the
+/// difference. The bidder that was outbid keeps their funds in their own
+/// escrow and pulls them back with withdraw_bid.
+pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let auction = &ctx.accounts.auction;
+    require!(now >= auction.start_time, Errors::AuctionNotStarted);
+    require!(now < auction.end_time, Errors::AuctionEnded);
+    require!(amount >= auction.min_next_bid()?, Errors::BidTooLow);
+
+    let top_up = amount.checked_sub(ctx.accounts.bid_escrow.amount).ok_or(Errors::BidTooLow)?;
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.bidder_token_account.to_account_info(),
+                to: ctx.accounts.bid_escrow.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info()
+            }
+        ),
+        top_up
+    )?;
+
+    let bid = &mut ctx.accounts.bid;
+    bid.auction = ctx.accounts.auction.key();
+    bid.bidder = ctx.accounts.bidder.key();
+    bid.payment_mint = ctx.accounts.payment_mint.key();
+    bid.amount = amount;
+    bid.bump = ctx.bumps.bid;
+
+    let auction = &mut ctx.accounts.auction;
+    auction.highest_bid = amount;
+    auction.highest_bidder = Some(ctx.accounts.bidder.key());
+    auction.extend_if_sniping(now)?;
+
+    emit!(BidPlaced {
+        asset_id: auction.asset_id,
+        bidder: ctx.accounts.bidder.key(),
+        amount,
+        end_time: auction.end_time,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::constants::*;
+use crate::events::PooledCustodyReconciled;
+use crate::state::PooledCustody;
+
+#[derive(Accounts)]
+pub struct ReconcilePooledCustody<'info> {
+    #[account(
+        seeds = [PooledCustody::SEED.as_bytes(), pooled_custody.mint.as_ref()],
+        bump = pooled_custody.bump
+    )]
+    pub pooled_custody: Account<'info, PooledCustody>,
+
+    #[account(
+        seeds = [constants::POOLED_VAULT, pooled_custody.mint.as_ref()],
+        bump
+    )]
+    pub pooled_vault: Account<'info, TokenAccount>,
+}
+
+pub fn reconcile_pooled_custody(ctx: Context<ReconcilePooledCustody>) -> Result<()> {
+    let pooled_custody = &ctx.accounts.pooled_custody;
+    let vault_balance = ctx.accounts.pooled_vault.amount;
+    let surplus = pooled_custody.reconcile(vault_balance)?;
+
+    emit!(PooledCustodyReconciled {
+        mint: pooled_custody.mint,
+        vault_balance,
+        recorded: pooled_custody.recorded,
+        surplus,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+use crate::events::UnlockRequested;
+use crate::state::{Config, StakeInfo};
+
+#[derive(Accounts)]
+pub struct RequestUnlock<'info> {
+    /// the depositor, or whoever the deposit was carried over to
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = beneficiary,
+        seeds = [StakeInfo::SEED.as_bytes(), stake_info.cnft.as_ref(), stake_info.depositor.as_ref(), stake_info.mint.as_ref()],
+        bump = stake_info.bump
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>
+}
+
+pub fn request_unlock(ctx: Context<RequestUnlock>) -> Result<()> {
+    let stake_info = &mut ctx.accounts.stake_info;
+    require!(stake_info.is_open(), Errors::DepositNotOpen);
+    let available_at = stake_info.request_unlock(Clock::get()?.unix_timestamp, ctx.accounts.config.unlock_cooldown)?;
+
+    emit!(UnlockRequested {
+        asset_id: stake_info.cnft,
+        depositor: stake_info.depositor,
+        amount: stake_info.amount,
+        available_at,
+    });
+
+    Ok(())
+}
\ No newline at end of file
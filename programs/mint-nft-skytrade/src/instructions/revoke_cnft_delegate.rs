@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::events::CnftDelegateRevoked;
+
+#[derive(Accounts)]
+pub struct RevokeCNFTDelegate<'info> {
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    pub leaf_owner: Signer<'info>,
+    /// CHECK: This account is checked in the downstream program
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn revoke_cnft_delegate<'info>(ctx: Context<'_, '_, '_, 'info, RevokeCNFTDelegate<'info>>,
+                                   root: [u8; 32],
+                                   data_hash: [u8; 32],
+                                   creator_hash: [u8; 32],
+                                   nonce: u64,
+                                   index: u32) -> Result<()> {
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    // revoking hands the delegate role back to the owner
+    DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.leaf_owner.to_account_info())
+        .previous_leaf_delegate(&ctx.accounts.previous_leaf_delegate.to_account_info())
+        .new_leaf_delegate(&ctx.accounts.leaf_owner.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .invoke()?;
+
+    emit!(CnftDelegateRevoked {
+        asset_id: get_asset_id(&ctx.accounts.merkle_tree.key(), nonce),
+        leaf_owner: ctx.accounts.leaf_owner.key(),
+        previous_leaf_delegate: ctx.accounts.previous_leaf_delegate.key(),
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Mint, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::AuctionSettled;
+use crate::state::{Auction, Bid, CollectionConfig, Config, CurrentLeaf, ParcelState};
+use crate::utils::{bps_amount, verify_leaf};
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        has_one = merkle_tree,
+        has_one = payment_mint,
+        seeds = [Auction::SEED.as_bytes(), auction.asset_id.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(mut)]
+    /// CHECK: checked against the auction
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: checked against the auction's highest bidder, gets the bid's rent back
+    pub winner: UncheckedAccount<'info>,
+
+    /// required when the parcel is delivered to the winner
+    #[account(mut)]
+    pub winning_bid: Option<Box<Account<'info, Bid>>>,
+    #[account(mut)]
+    pub winning_bid_escrow: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [CollectionConfig::SEED.as_bytes(), auction.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = seller
+    )]
+    pub seller_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = collection_config.authority
+    )]
+    pub royalty_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), auction.asset_id.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+/// Pays the seller out of the winning bid and hands the parcel over. When
+/// the parcel can't be delivered, because it is locked or leased or because
+/// `stale_leaf` proves the auction is no longer the leaf delegate, the bid
+/// stays in the winner's escrow for withdraw_bid.
+pub fn settle_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
+                             root: [u8; 32],
+                             stale_leaf: Option<CurrentLeaf>) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    require!(Clock::get()?.unix_timestamp >= auction.end_time, Errors::AuctionNotEnded);
+
+    let auction_seeds: &[&[&[u8]]] = &[&[
+        Auction::SEED.as_bytes(),
+        auction.asset_id.as_ref(),
+        &[auction.bump]
+    ]];
+
+    let mut sold = false;
+    if let Some(winner) = auction.highest_bidder {
+        require_keys_eq!(ctx.accounts.winner.key(), winner, Errors::NotAuctionWinner);
+
+        let undeliverable = match stale_leaf {
+            Some(current_leaf) => {
+                require!(current_leaf.owner != auction.seller || current_leaf.delegate != auction.key(),
+                    Errors::LeafNotStale);
+                verify_leaf(
+                    &ctx.accounts.compression_program.to_account_info(),
+                    &ctx.accounts.merkle_tree.to_account_info(),
+                    ctx.remaining_accounts,
+                    current_leaf.root,
+                    &current_leaf.to_leaf_schema(auction.asset_id, auction.nonce),
+                    auction.index
+                )?;
+                true
+            }
+            None => ParcelState::is_transfer_blocked(&ctx.accounts.parcel_state)?,
+        };
+
+        if !undeliverable {
+            let (Some(winning_bid), Some(winning_bid_escrow)) =
+                (&ctx.accounts.winning_bid, &ctx.accounts.winning_bid_escrow) else {
+                return err!(Errors::InvalidBidAccount);
+            };
+            let (bid_key, _) = Pubkey::find_program_address(&[
+                Bid::SEED.as_bytes(),
+                auction.key().as_ref(),
+                winner.as_ref()
+            ], ctx.program_id);
+            let (escrow_key, _) = Pubkey::find_program_address(&[
+                constants::BID_ESCROW,
+                bid_key.as_ref()
+            ], ctx.program_id);
+            require!(winning_bid.key() == bid_key && winning_bid_escrow.key() == escrow_key,
+                Errors::InvalidBidAccount);
+
+            let bid_seeds: &[&[&[u8]]] = &[&[
+                Bid::SEED.as_bytes(),
+                winning_bid.auction.as_ref(),
+                winning_bid.bidder.as_ref(),
+                &[winning_bid.bump]
+            ]];
+            let pay_from_bid = |destination: AccountInfo<'info>, amount: u64| -> Result<()> {
+                if amount == 0 {
+                    return Ok(());
+                }
+                transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: winning_bid_escrow.to_account_info(),
+                            to: destination,
+                            authority: winning_bid.to_account_info()
+                        },
+                        bid_seeds
+                    ),
+                    amount
+                )
+            };
+
+            let protocol_fee = bps_amount(auction.highest_bid, ctx.accounts.config.fees.sale_bps)?;
+            let royalty = bps_amount(auction.highest_bid, auction.royalty_bps)?;
+            let seller_proceeds = auction.highest_bid
+                .checked_sub(protocol_fee)
+                .and_then(|amount| amount.checked_sub(royalty))
+                .ok_or(Errors::FeesExceedPrice)?;
+            pay_from_bid(ctx.accounts.seller_token_account.to_account_info(), seller_proceeds)?;
+            pay_from_bid(ctx.accounts.royalty_token_account.to_account_info(), royalty)?;
+            pay_from_bid(ctx.accounts.treasury_vault.to_account_info(), protocol_fee)?;
+
+            // anything left over stays with the bid for withdraw_bid
+            if winning_bid_escrow.amount == auction.highest_bid {
+                close_account(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        CloseAccount {
+                            account: winning_bid_escrow.to_account_info(),
+                            destination: ctx.accounts.winner.to_account_info(),
+                            authority: winning_bid.to_account_info()
+                        },
+                        bid_seeds
+                    )
+                )?;
+                winning_bid.close(ctx.accounts.winner.to_account_info())?;
+            }
+
+            // the auction pda is the leaf delegate and signs the transfer to the winner
+            let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+                .iter()
+                .map(|account| (account, false, false))
+                .collect();
+
+            TransferCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+                .tree_config(&ctx.accounts.tree_authority.to_account_info())
+                .leaf_owner(&ctx.accounts.seller.to_account_info(), false)
+                .leaf_delegate(&auction.to_account_info(), true)
+                .new_leaf_owner(&ctx.accounts.winner.to_account_info())
+                .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+                .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+                .compression_program(&ctx.accounts.compression_program.to_account_info())
+                .system_program(&ctx.accounts.system_program.to_account_info())
+                .add_remaining_accounts(&proof_accounts)
+                .root(root)
+                .data_hash(auction.data_hash)
+                .creator_hash(auction.creator_hash)
+                .nonce(auction.nonce)
+                .index(auction.index)
+                .invoke_signed(auction_seeds)?;
+            sold = true;
+        }
+    }
+
+    emit!(AuctionSettled {
+        asset_id: auction.asset_id,
+        seller: auction.seller,
+        winner: auction.highest_bidder,
+        amount: auction.highest_bid,
+        sold,
+    });
+
+    Ok(())
+}
\ No newline at end of file
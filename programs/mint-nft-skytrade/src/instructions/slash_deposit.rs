@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::PenaltyRecorded;
+use crate::state::{Config, ParcelState, PenaltyKind, PenaltyRecord, PooledCustody, RewardPool, StakeInfo};
+
+#[derive(Accounts)]
+pub struct SlashDeposit<'info> {
+    #[account(mut)]
+    pub arbitrator: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = arbitrator,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    /// CHECK: checked against the stake info, gets its rent back if the slash empties it
+    pub depositor: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = depositor,
+        seeds = [StakeInfo::SEED.as_bytes(), stake_info.cnft.as_ref(), stake_info.depositor.as_ref(), stake_info.mint.as_ref()],
+        bump = stake_info.bump
+    )]
+    pub stake_info: Box<Account<'info, StakeInfo>>,
+
+    #[account(
+        mut,
+        seeds = [ParcelState::SEED.as_bytes(), stake_info.cnft.as_ref()],
+        bump = parcel_state.bump
+    )]
+    pub parcel_state: Account<'info, ParcelState>,
+
+    #[account(
+        mut,
+        seeds = [constants::STAKE_VAULT, stake_info.cnft.as_ref(), stake_info.mint.as_ref()],
+        bump
+    )]
+    pub cnft_stake_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [PooledCustody::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump = pooled_custody.bump
+    )]
+    pub pooled_custody: Option<Box<Account<'info, PooledCustody>>>,
+
+    #[account(
+        mut,
+        seeds = [constants::POOLED_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub pooled_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [constants::VAULT_AUTHORITY],
+        bump
+    )]
+    /// CHECK: signs for every stake vault, holds no data
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized for mints with a reward pool
+    pub reward_pool: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [PenaltyRecord::SEED.as_bytes(), config.penalties.to_le_bytes().as_ref()],
+        bump,
+        payer = arbitrator,
+        space = PenaltyRecord::SIZE
+    )]
+    pub penalty_record: Box<Account<'info, PenaltyRecord>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn slash_deposit(ctx: Context<SlashDeposit>, amount: u64, reason_code: u16) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let stake_info = &mut ctx.accounts.stake_info;
+    stake_info.record_slash(amount)?;
+    // a slash that takes everything ends the entry, so it no longer counts
+    // as a staker and lock_fund can open it afresh
+    let emptied = stake_info.amount == 0;
+    if let Some(mut reward_pool) = RewardPool::load(&ctx.accounts.reward_pool)? {
+        reward_pool.update(now)?;
+        if emptied {
+            reward_pool.close_stake(stake_info)?;
+        } else {
+            reward_pool.sync_stake(stake_info)?;
+        }
+        reward_pool.save(&ctx.accounts.reward_pool)?;
+    }
+    if emptied {
+        stake_info.record_exit();
+    }
+    ctx.accounts.parcel_state.record_unlock(amount, emptied)?;
+
+    let index = ctx.accounts.config.next_penalty()?;
+    ctx.accounts.penalty_record.bump = ctx.bumps.penalty_record;
+    ctx.accounts.penalty_record.record(PenaltyKind::Slash, stake_info, amount, reason_code,
+        ctx.accounts.arbitrator.key())?;
+
+    let vault = match (stake_info.pooled, &ctx.accounts.cnft_stake_vault, &ctx.accounts.pooled_vault) {
+        (false, Some(vault), _) | (true, _, Some(vault)) => vault,
+        _ => return err!(Errors::CustodyMismatch)
+    };
+    let vault_seeds: &[&[&[u8]]] = &[&[constants::VAULT_AUTHORITY, &[ctx.bumps.vault_authority]]];
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: ctx.accounts.treasury_vault.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info()
+            },
+            vault_seeds
+        ),
+        amount
+    )?;
+
+    if stake_info.pooled {
+        let pooled_custody = ctx.accounts.pooled_custody.as_mut().ok_or(Errors::CustodyMismatch)?;
+        pooled_custody.record_withdrawal(amount, emptied)?;
+        pooled_custody.reconcile(vault.amount.checked_sub(amount).ok_or(Errors::MathOverflow)?)?;
+    }
+
+    emit!(PenaltyRecorded {
+        index,
+        kind: PenaltyKind::Slash,
+        asset_id: stake_info.cnft,
+        depositor: stake_info.depositor,
+        mint: stake_info.mint,
+        amount,
+        reason_code,
+        authority: ctx.accounts.arbitrator.key(),
+    });
+
+    if emptied && !stake_info.has_rewards_owed() {
+        stake_info.close(ctx.accounts.depositor.to_account_info())?;
+    }
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::instructions::DelegateCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::errors::Errors;
+use crate::events::AuctionStarted;
+use crate::state::{Auction, CollectionConfig, Listing, ParcelMetadata, ParcelState, TokenWhitelist};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AuctionParams {
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub duration: i64,
+    pub anti_snipe_window: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], nonce: u64)]
+pub struct StartAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        init,
+        seeds = [Auction::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        payer = seller,
+        space = Auction::SIZE
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>,
+
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [Listing::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump
+    )]
+    /// CHECK: must not be initialized, the parcel can't be auctioned while it is listed
+    pub listing: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [TokenWhitelist::SEED.as_bytes()],
+        bump
+    )]
+    pub whitelist: Box<Account<'info, TokenWhitelist>>,
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [merkle_tree.key().as_ref()],
+        bump,
+        seeds::program = bubblegum_program.key(),
+    )]
+    /// CHECK: This account is checked in the downstream program
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: This account is checked in the downstream program
+    pub previous_leaf_delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn start_auction<'info>(ctx: Context<'_, '_, '_, 'info, StartAuction<'info>>,
+                            root: [u8; 32],
+                            nonce: u64,
+                            index: u32,
+                            metadata: ParcelMetadata,
+                            params: AuctionParams) -> Result<()> {
+    require!(params.reserve_price > 0 && params.min_increment > 0, Errors::InvalidAuctionParams);
+    require!(params.duration > 0 && params.anti_snipe_window >= 0, Errors::InvalidAuctionParams);
+    require!(ctx.accounts.whitelist.tokens.contains(&ctx.accounts.payment_mint.key()), Errors::TokenNotWhitelisted);
+    ParcelState::ensure_transferable(&ctx.accounts.parcel_state)?;
+    // cancel_listing or close_stale_listing close the listing first
+    require!(ctx.accounts.listing.data_is_empty(), Errors::ParcelOnSale);
+
+    // the delegate cpi verifies these hashes against the tree
+    let collection_mint = ctx.accounts.collection_config.collection_mint;
+    let data_hash = hash_metadata(&metadata.to_metadata_args(collection_mint))
+        .map_err(|_| Errors::InvalidMetadata)?;
+    let creator_hash = hash_creators(&[]);
+
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    DelegateCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.seller.to_account_info())
+        .previous_leaf_delegate(&ctx.accounts.previous_leaf_delegate.to_account_info())
+        .new_leaf_delegate(&ctx.accounts.auction.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .invoke()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let auction = &mut ctx.accounts.auction;
+    auction.seller = ctx.accounts.seller.key();
+    auction.asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+    auction.merkle_tree = ctx.accounts.merkle_tree.key();
+    auction.nonce = nonce;
+    auction.index = index;
+    auction.data_hash = data_hash;
+    auction.creator_hash = creator_hash;
+    auction.collection_mint = collection_mint;
+    auction.royalty_bps = metadata.seller_fee_basis_points;
+    auction.payment_mint = ctx.accounts.payment_mint.key();
+    auction.reserve_price = params.reserve_price;
+    auction.min_increment = params.min_increment;
+    auction.start_time = now;
+    auction.end_time = now.checked_add(params.duration).ok_or(Errors::MathOverflow)?;
+    auction.anti_snipe_window = params.anti_snipe_window;
+    auction.highest_bid = 0;
+    auction.highest_bidder = None;
+    auction.bump = ctx.bumps.auction;
+
+    emit!(AuctionStarted {
+        asset_id: auction.asset_id,
+        seller: auction.seller,
+        payment_mint: auction.payment_mint,
+        reserve_price: auction.reserve_price,
+        end_time: auction.end_time,
+    });
+
+    Ok(())
+}
\ No newline at end of file
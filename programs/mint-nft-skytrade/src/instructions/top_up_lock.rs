@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::LockToppedUp;
+use crate::state::{Asset, Config, ParcelState, PooledCustody, RewardPool, StakeInfo};
+use crate::utils::bps_amount;
+
+#[derive(Accounts)]
+pub struct TopUpLock<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = depositor,
+        seeds = [StakeInfo::SEED.as_bytes(), stake_info.cnft.as_ref(), stake_info.depositor.as_ref(), stake_info.mint.as_ref()],
+        bump = stake_info.bump
+    )]
+    pub stake_info: Box<Account<'info, StakeInfo>>,
+
+    #[account(
+        address = stake_info.asset
+    )]
+    /// CHECK: the asset the deposit was priced against
+    pub asset_info: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ParcelState::SEED.as_bytes(), stake_info.cnft.as_ref()],
+        bump = parcel_state.bump
+    )]
+    pub parcel_state: Account<'info, ParcelState>,
+
+    #[account(
+        mut,
+        token::mint = stake_info.mint,
+        token::authority = depositor
+    )]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [constants::STAKE_VAULT, stake_info.cnft.as_ref(), stake_info.mint.as_ref()],
+        bump
+    )]
+    pub cnft_stake_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [PooledCustody::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump = pooled_custody.bump
+    )]
+    pub pooled_custody: Option<Box<Account<'info, PooledCustody>>>,
+
+    #[account(
+        mut,
+        seeds = [constants::POOLED_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub pooled_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized for mints with a reward pool
+    pub reward_pool: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn top_up_lock(ctx: Context<TopUpLock>, amount: u64) -> Result<()> {
+    require!(amount > 0, Errors::InvalidLockAmount);
+    require!(ctx.accounts.stake_info.is_open(), Errors::InSufficientToken);
+    let asset = Asset::load(&ctx.accounts.asset_info, ctx.accounts.stake_info.cnft)?;
+    let vault = match (ctx.accounts.stake_info.pooled, &ctx.accounts.cnft_stake_vault, &ctx.accounts.pooled_vault) {
+        (false, Some(vault), _) | (true, _, Some(vault)) => vault.to_account_info(),
+        _ => return err!(Errors::CustodyMismatch)
+    };
+
+    let stake_info = &mut ctx.accounts.stake_info;
+    let now = Clock::get()?.unix_timestamp;
+    stake_info.record_deposit(amount, asset.price, ctx.accounts.asset_info.key())?;
+    if let Some(mut reward_pool) = RewardPool::load(&ctx.accounts.reward_pool)? {
+        reward_pool.update(now)?;
+        reward_pool.sync_stake(stake_info)?;
+        reward_pool.save(&ctx.accounts.reward_pool)?;
+    }
+
+    let parcel_state = &mut ctx.accounts.parcel_state;
+    parcel_state.record_lock(amount, false)?;
+
+    let lock_fee = bps_amount(amount, ctx.accounts.config.fees.lock_bps)?;
+    if lock_fee > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.depositor_token_account.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info()
+                }
+            ),
+            lock_fee
+        )?;
+    }
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.depositor_token_account.to_account_info(),
+                to: vault,
+                authority: ctx.accounts.depositor.to_account_info()
+            }
+        ),
+        amount
+    )?;
+
+    if let (Some(pooled_custody), Some(pooled_vault)) =
+        (&mut ctx.accounts.pooled_custody, &mut ctx.accounts.pooled_vault) {
+        pooled_custody.record_deposit(amount, false)?;
+        pooled_vault.reload()?;
+        pooled_custody.reconcile(pooled_vault.amount)?;
+    }
+
+    emit!(LockToppedUp {
+        asset_id: ctx.accounts.stake_info.cnft,
+        depositor: ctx.accounts.stake_info.depositor,
+        amount,
+        total: ctx.accounts.stake_info.amount,
+    });
+
+    Ok(())
+}
\ No newline at end of file
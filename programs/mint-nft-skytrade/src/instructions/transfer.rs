@@ -1,12 +1,13 @@
-
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use mpl_bubblegum::instructions::TransferCpiBuilder;
+use mpl_bubblegum::utils::get_asset_id;
 use spl_account_compression::{program::SplAccountCompression, Noop};
 use crate::{MplBubblegum};
-use solana_program::program::invoke_signed;
-use solana_program::instruction::Instruction;
-
-
-const TRANSFER_DISCRIMINATOR: &[u8; 8] = &[163, 52, 200, 231, 140, 3, 69, 186];
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::{DepositCarriedOver, RewardsClaimed};
+use crate::state::{ParcelState, RefundRule, RewardPool, StakeInfo};
 
 pub fn transfer_cnft<'info>(
     ctx: Context<'_, '_, '_, 'info, Transfer<'info>>,
@@ -15,6 +16,7 @@ pub fn transfer_cnft<'info>(
     creator_hash: [u8; 32],
     nonce: u64,
     index: u32,
+    carry_deposit: bool,
 ) -> Result<()> {
     msg!(
         "attempting to send nft {} from tree {}",
@@ -22,59 +24,153 @@ pub fn transfer_cnft<'info>(
         ctx.accounts.merkle_tree.key()
     );
 
-    let mut accounts: Vec<solana_program::instruction::AccountMeta> = vec![
-        AccountMeta::new_readonly(ctx.accounts.tree_authority.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.leaf_owner.key(), true),
-        AccountMeta::new_readonly(ctx.accounts.leaf_owner.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.new_leaf_owner.key(), false),
-        AccountMeta::new(ctx.accounts.merkle_tree.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.log_wrapper.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.compression_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-    ];
+    // either the owner or the delegate of the leaf may move it
+    let authority = ctx.accounts.authority.key();
+    let owner_signed = authority == ctx.accounts.leaf_owner.key();
+    let delegate_signed = authority == ctx.accounts.leaf_delegate.key();
+    require!(owner_signed || delegate_signed, Errors::NotOwnerOrDelegate);
 
-    let mut data: Vec<u8> = vec![];
-    data.extend(TRANSFER_DISCRIMINATOR);
-    data.extend(root);
-    data.extend(data_hash);
-    data.extend(creator_hash);
-    data.extend(nonce.to_le_bytes());
-    data.extend(index.to_le_bytes());
+    // a leased parcel can't move, and a locked one only moves when the
+    // leaf owner hands every deposit to the receiver; bubblegum proves the
+    // owner when it verifies the leaf below
+    let mut proof = ctx.remaining_accounts;
+    if let Some(parcel_state) = ParcelState::load(&ctx.accounts.parcel_state)? {
+        require!(!parcel_state.is_leased(Clock::get()?.unix_timestamp), Errors::LeaseActive);
+        if parcel_state.is_locked() {
+            require!(carry_deposit, Errors::CnftLocked);
+            require!(owner_signed, Errors::NotDepositor);
 
-    let mut account_infos: Vec<AccountInfo> = vec![
-        ctx.accounts.tree_authority.to_account_info(),
-        ctx.accounts.leaf_owner.to_account_info(),
-        ctx.accounts.leaf_owner.to_account_info(),
-        ctx.accounts.new_leaf_owner.to_account_info(),
-        ctx.accounts.merkle_tree.to_account_info(),
-        ctx.accounts.log_wrapper.to_account_info(),
-        ctx.accounts.compression_program.to_account_info(),
-        ctx.accounts.system_program.to_account_info(),
-    ];
+            // the remaining accounts hold the merkle proof followed by one
+            // (stake info, reward pool, reward vault, beneficiary's reward
+            // token account) group per live stake info entry of the cNFT
+            let ledger_len = parcel_state.stakers as usize * 4;
+            require!(ctx.remaining_accounts.len() >= ledger_len, Errors::InvalidRefundAccount);
+            let (merkle_proof, ledger) = ctx.remaining_accounts.split_at(ctx.remaining_accounts.len() - ledger_len);
+            proof = merkle_proof;
+
+            let now = Clock::get()?.unix_timestamp;
+            let mut carried: Vec<Pubkey> = Vec::with_capacity(parcel_state.stakers as usize);
+            for entry in ledger.chunks(4) {
+                let (stake_info_account, reward_pool_account, reward_vault, reward_account) =
+                    (&entry[0], &entry[1], &entry[2], &entry[3]);
+                require!(!carried.contains(stake_info_account.key), Errors::InvalidRefundAccount);
+                carried.push(stake_info_account.key());
+                let mut stake_info = StakeInfo::load(stake_info_account, &parcel_state.cnft, ctx.program_id)?;
+                require!(stake_info.is_open(), Errors::InvalidRefundAccount);
+                // an owner-refunded deposit changes beneficiary, so what it
+                // earned so far goes to the outgoing one first
+                if stake_info.refund_rule == RefundRule::Owner {
+                    pay_out_rewards(&mut stake_info, reward_pool_account, reward_vault, reward_account,
+                                    &ctx.accounts.token_program, ctx.program_id, now)?;
+                }
+                let previous_owner = stake_info.owner;
+                stake_info.carry_over(ctx.accounts.new_leaf_owner.key());
+                stake_info.save(stake_info_account)?;
+
+                emit!(DepositCarriedOver {
+                    asset_id: parcel_state.cnft,
+                    depositor: stake_info.depositor,
+                    previous_owner,
+                    new_owner: stake_info.owner,
+                    amount: stake_info.amount,
+                });
+            }
+        }
+    }
 
     // add "accounts" (hashes) that make up the merkle proof
-    for acc in ctx.remaining_accounts.iter() {
-        accounts.push(AccountMeta::new_readonly(acc.key(), false));
-        account_infos.push(acc.to_account_info());
+    let proof_accounts: Vec<(&AccountInfo, bool, bool)> = proof
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    TransferCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_authority.to_account_info())
+        .leaf_owner(&ctx.accounts.leaf_owner.to_account_info(), owner_signed)
+        .leaf_delegate(&ctx.accounts.leaf_delegate.to_account_info(), delegate_signed)
+        .new_leaf_owner(&ctx.accounts.new_leaf_owner.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&proof_accounts)
+        .root(root)
+        .data_hash(data_hash)
+        .creator_hash(creator_hash)
+        .nonce(nonce)
+        .index(index)
+        .invoke()?;
+
+    Ok(())
+}
+
+/// Settles the stake's rewards and pays them to its current beneficiary.
+/// Whatever an underfunded pool can't pay is forfeited, so it never passes
+/// to the new owner with the deposit.
+fn pay_out_rewards<'info>(stake_info: &mut StakeInfo,
+                          reward_pool_account: &AccountInfo<'info>,
+                          reward_vault: &AccountInfo<'info>,
+                          reward_account: &AccountInfo<'info>,
+                          token_program: &Program<'info, Token>,
+                          program_id: &Pubkey,
+                          now: i64) -> Result<()> {
+    let (reward_pool_key, _) = Pubkey::find_program_address(
+        &[RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+        program_id
+    );
+    require_keys_eq!(reward_pool_account.key(), reward_pool_key, Errors::InvalidRefundAccount);
+    let Some(mut reward_pool) = RewardPool::load(reward_pool_account)? else {
+        return Ok(());
+    };
+    reward_pool.update(now)?;
+    reward_pool.sync_stake(stake_info)?;
+    reward_pool.save(reward_pool_account)?;
+
+    let (reward_vault_key, _) = Pubkey::find_program_address(
+        &[constants::REWARD_VAULT, reward_pool_key.as_ref()],
+        program_id
+    );
+    require_keys_eq!(reward_vault.key(), reward_vault_key, Errors::InvalidRefundAccount);
+    let amount = stake_info.pending_rewards
+        .min(TokenAccount::try_deserialize(&mut &reward_vault.try_borrow_data()?[..])?.amount);
+    stake_info.pending_rewards = 0;
+    if amount == 0 {
+        return Ok(());
     }
 
-    msg!("manual cpi call");
-     invoke_signed(
-      &Instruction {
-            program_id: ctx.accounts.bubblegum_program.key(),
-            accounts,
-            data,
-        },
-        &account_infos[..],
-        &[&[
-            ctx.accounts.merkle_tree.key().as_ref(),
-            &[ctx.bumps.tree_authority]
-        ]],
-    )
-    .map_err(Into::into)
+    let destination = TokenAccount::try_deserialize(&mut &reward_account.try_borrow_data()?[..])?;
+    require!(destination.owner == stake_info.beneficiary && destination.mint == reward_pool.reward_mint,
+        Errors::InvalidRefundAccount);
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Transfer {
+                from: reward_vault.to_account_info(),
+                to: reward_account.to_account_info(),
+                authority: reward_pool_account.to_account_info()
+            },
+            &[&[
+                RewardPool::SEED.as_bytes(),
+                reward_pool.stake_mint.as_ref(),
+                &[reward_pool.bump]
+            ]]
+        ),
+        amount
+    )?;
+
+    emit!(RewardsClaimed {
+        asset_id: stake_info.cnft,
+        beneficiary: stake_info.beneficiary,
+        reward_mint: reward_pool.reward_mint,
+        amount,
+        still_owed: 0,
+    });
+
+    Ok(())
 }
 
 #[derive(Accounts)]
+#[instruction(root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], nonce: u64)]
 pub struct Transfer<'info> {
     #[account(
         seeds = [merkle_tree.key().as_ref()],
@@ -83,16 +179,27 @@ pub struct Transfer<'info> {
     )]
          /// CHECK: unsafe
     pub tree_authority: UncheckedAccount<'info>,
-    #[account(mut)] // Add mut here to fix the payer error
-    /// CHECK: This account is the intended recipient of the NFT transfer
-    pub leaf_owner:Signer<'info>, // sender (the vault in our case)
+    /// the leaf owner or its delegate
+    pub authority: Signer<'info>,
+    /// CHECK: This account is checked in the downstream program
+    pub leaf_owner: UncheckedAccount<'info>, // sender
+    /// CHECK: This account is checked in the downstream program
+    pub leaf_delegate: UncheckedAccount<'info>, // the owner when no delegate is set
     /// CHECK: This account is neither written to nor read from.
     pub new_leaf_owner: UncheckedAccount<'info>, // receiver
     #[account(mut)]
     /// CHECK: This account is modified in the downstream program
     pub merkle_tree: UncheckedAccount<'info>,
+    #[account(
+        seeds = [ParcelState::SEED.as_bytes(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized once funds were locked against the cNFT
+    pub parcel_state: UncheckedAccount<'info>,
     pub log_wrapper: Program<'info, Noop>,
     pub compression_program: Program<'info, SplAccountCompression>,
     pub bubblegum_program: Program<'info, MplBubblegum>,
     pub system_program: Program<'info, System>,
+    /// pays out the rewards of deposits carried over to the receiver
+    pub token_program: Program<'info, Token>,
 }
\ No newline at end of file
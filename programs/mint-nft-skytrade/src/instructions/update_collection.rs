@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::state::CollectionConfig;
+
+#[derive(Accounts)]
+pub struct UpdateCollection<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Account<'info, CollectionConfig>
+}
+
+pub fn update_collection(ctx: Context<UpdateCollection>,
+                         max_supply: Option<u64>,
+                         allowed_uri_schemes: u8) -> Result<()> {
+    ctx.accounts.collection_config.set_allowed_uri_schemes(allowed_uri_schemes)?;
+    ctx.accounts.collection_config.set_max_supply(max_supply)
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, FeeSchedule};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>
+}
+
+pub fn update_config(ctx: Context<UpdateConfig>, treasurer: Pubkey, fees: FeeSchedule, unlock_cooldown: i64) -> Result<()> {
+    ctx.accounts.config.treasurer = treasurer;
+    ctx.accounts.config.set_unlock_cooldown(unlock_cooldown)?;
+    ctx.accounts.config.set_fees(fees)
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+use crate::events::ListingUpdated;
+use crate::state::Listing;
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        has_one = seller,
+        seeds = [Listing::SEED.as_bytes(), listing.asset_id.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>
+}
+
+pub fn update_listing(ctx: Context<UpdateListing>, price: u64) -> Result<()> {
+    require!(price > 0, Errors::InvalidPrice);
+    let listing = &mut ctx.accounts.listing;
+    require!(listing.dutch.is_none(), Errors::DutchListingNotUpdatable);
+    let previous_price = listing.price;
+    listing.price = price;
+
+    emit!(ListingUpdated {
+        asset_id: listing.asset_id,
+        previous_price,
+        price,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::Metadata;
+use mpl_bubblegum::hash::hash_metadata;
+use mpl_bubblegum::instructions::UpdateMetadataCpiBuilder;
+use mpl_bubblegum::types::UpdateArgs;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::{program::SplAccountCompression, Noop};
+use crate::MplBubblegum;
+use crate::errors::Errors;
+use crate::events::ParcelMetadataUpdated;
+use crate::state::{CollectionConfig, ParcelMetadata};
+
+#[derive(Accounts)]
+pub struct UpdateParcelMetadata<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        seeds = [CollectionConfig::SEED.as_bytes(), collection_mint.key().as_ref()],
+        bump = collection_config.bump
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub tree_config: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"tree_owner", merkle_tree.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This account used as a signing PDA only
+    pub central_authority: UncheckedAccount<'info>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub collection_mint: UncheckedAccount<'info>,
+    /// CHECK: This account is checked in the downstream program
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: This account is neither written to nor read from.
+    pub leaf_owner: UncheckedAccount<'info>,
+    /// CHECK: This account is neither written to nor read from.
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: This account is modified in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub bubblegum_program: Program<'info, MplBubblegum>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_parcel_metadata<'info>(ctx: Context<'_, '_, '_, 'info, UpdateParcelMetadata<'info>>,
+                                     root: [u8; 32],
+                                     nonce: u64,
+                                     index: u32,
+                                     current_metadata: ParcelMetadata,
+                                     name: Option<String>,
+                                     uri: Option<String>) -> Result<()> {
+    let collection_mint = ctx.accounts.collection_mint.key();
+    let mut updated_metadata = current_metadata.clone();
+    if let Some(name) = name.clone() {
+        updated_metadata.name = name;
+    }
+    if let Some(uri) = uri.clone() {
+        updated_metadata.uri = uri;
+    }
+    ctx.accounts.collection_config.validate_metadata(
+        &updated_metadata.name,
+        &updated_metadata.symbol,
+        &updated_metadata.uri,
+        updated_metadata.seller_fee_basis_points,
+    )?;
+
+    let current_metadata = current_metadata.to_metadata_args(collection_mint);
+    let old_data_hash = hash_metadata(&current_metadata).map_err(|_| Errors::InvalidMetadata)?;
+    let new_data_hash = hash_metadata(&updated_metadata.to_metadata_args(collection_mint))
+        .map_err(|_| Errors::InvalidMetadata)?;
+
+    // proof nodes are passed as remaining accounts
+    let remaining_accounts: Vec<(&AccountInfo, bool, bool)> = ctx.remaining_accounts
+        .iter()
+        .map(|account| (account, false, false))
+        .collect();
+
+    UpdateMetadataCpiBuilder::new(&ctx.accounts.bubblegum_program.to_account_info())
+        .tree_config(&ctx.accounts.tree_config.to_account_info())
+        .authority(&ctx.accounts.central_authority.to_account_info())
+        .collection_mint(Some(&ctx.accounts.collection_mint.to_account_info()))
+        .collection_metadata(Some(&ctx.accounts.collection_metadata.to_account_info()))
+        .collection_authority_record_pda(Some(&ctx.accounts.bubblegum_program.to_account_info()))
+        .leaf_owner(&ctx.accounts.leaf_owner.to_account_info())
+        .leaf_delegate(&ctx.accounts.leaf_delegate.to_account_info())
+        .payer(&ctx.accounts.authority.to_account_info())
+        .merkle_tree(&ctx.accounts.merkle_tree.to_account_info())
+        .log_wrapper(&ctx.accounts.log_wrapper.to_account_info())
+        .compression_program(&ctx.accounts.compression_program.to_account_info())
+        .token_metadata_program(&ctx.accounts.token_metadata_program.to_account_info())
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .add_remaining_accounts(&remaining_accounts)
+        .root(root)
+        .nonce(nonce)
+        .index(index)
+        .current_metadata(current_metadata)
+        .update_args(UpdateArgs {
+            name,
+            uri,
+            ..UpdateArgs::default()
+        })
+        .invoke_signed(&[&[
+            b"tree_owner",
+            ctx.accounts.merkle_tree.key().as_ref(),
+            &[ctx.bumps.central_authority]
+        ]])?;
+
+    emit!(ParcelMetadataUpdated {
+        asset_id: get_asset_id(&ctx.accounts.merkle_tree.key(), nonce),
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+        old_data_hash,
+        new_data_hash,
+    });
+
+    Ok(())
+}
\ No newline at end of file
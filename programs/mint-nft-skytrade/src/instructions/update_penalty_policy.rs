@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::events::PenaltyPolicyUpdated;
+use crate::state::{Config, PenaltyPolicy};
+
+#[derive(Accounts)]
+pub struct UpdatePenaltyPolicy<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>
+}
+
+pub fn update_penalty_policy(ctx: Context<UpdatePenaltyPolicy>, arbitrator: Pubkey, penalty_policy: PenaltyPolicy) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.arbitrator = arbitrator;
+    config.set_penalty_policy(penalty_policy)?;
+
+    emit!(PenaltyPolicyUpdated {
+        arbitrator,
+        min_lock_duration: penalty_policy.min_lock_duration,
+        early_unlock_penalty_bps: penalty_policy.early_unlock_penalty_bps,
+    });
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::events::RewardPoolUpdated;
+use crate::state::{Config, RewardPool};
+
+#[derive(Accounts)]
+pub struct UpdateRewardPool<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), reward_pool.stake_mint.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>
+}
+
+pub fn update_reward_pool(ctx: Context<UpdateRewardPool>, emission_rate: u64) -> Result<()> {
+    // emissions up to now are accrued at the old rate
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.update(Clock::get()?.unix_timestamp)?;
+    reward_pool.emission_rate = emission_rate;
+
+    emit!(RewardPoolUpdated {
+        stake_mint: reward_pool.stake_mint,
+        reward_mint: reward_pool.reward_mint,
+        emission_rate,
+    });
+
+    Ok(())
+}
\ No newline at end of file
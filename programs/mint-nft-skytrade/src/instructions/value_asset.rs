@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::events::AssetValued;
+use crate::state::{Asset, AssetValuation, Config};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct ValueAsset<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        has_one = admin,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // lock_fund, top_up_lock, partial_unlock and mint_cnft read the price
+    #[account(
+        init_if_needed,
+        seeds = [Asset::SEED.as_bytes(), asset_id.as_ref()],
+        bump,
+        payer = admin,
+        space = Asset::SIZE
+    )]
+    pub asset_info: Account<'info, Asset>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn value_asset(ctx: Context<ValueAsset>, asset_id: Pubkey, valuation: AssetValuation) -> Result<()> {
+    let asset_info = &mut ctx.accounts.asset_info;
+    asset_info.id = asset_id;
+    asset_info.set_valuation(valuation, ctx.accounts.admin.key(), Clock::get()?.unix_timestamp)?;
+
+    emit!(AssetValued {
+        asset_id,
+        price: asset_info.price,
+        authority: asset_info.authority,
+    });
+
+    Ok(())
+}
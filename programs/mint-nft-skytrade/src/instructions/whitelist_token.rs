@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
-use crate::state::{TokenWhitelist, WhitelistTokenAccount};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::constants::*;
+use crate::state::{TokenWhitelist, Treasury, WhitelistTokenAccount};
 
 #[derive(Accounts)]
 pub struct WhitelistToken<'info> {
@@ -13,7 +14,27 @@ pub struct WhitelistToken<'info> {
     )]
     pub whitelist: Account<'info, TokenWhitelist>,
     pub mint: Account<'info, Mint>,
-    pub system_program: Program<'info, System>
+
+    #[account(
+        seeds = [Treasury::SEED.as_bytes(), mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: only the vault authority, initialized by configure_treasury
+    pub treasury: UncheckedAccount<'info>,
+
+    // every whitelisted mint gets its treasury vault up front, so fee-taking
+    // instructions can always pass it even while their fee is zero
+    #[account(
+        init_if_needed,
+        seeds = [constants::TREASURY_VAULT, mint.key().as_ref()],
+        bump,
+        payer = signer,
+        token::mint = mint,
+        token::authority = treasury
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
 }
 
 pub fn whitelist_token(ctx: Context<WhitelistToken>) -> Result<()> {
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::BidWithdrawn;
+use crate::state::{Auction, Bid};
+
+#[derive(Accounts)]
+pub struct WithdrawBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = auction,
+        seeds = [Bid::SEED.as_bytes(), auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Box<Account<'info, Bid>>,
+
+    #[account(
+        mut,
+        seeds = [constants::BID_ESCROW, bid.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: checked against the bid, may already be closed by settlement
+    pub auction: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = bid.payment_mint,
+        token::authority = bidder
+    )]
+    pub bidder_token_account: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>
+}
+
+/// Returns an escrowed bid to its bidder once it no longer leads the
+/// auction, either because it was outbid or because the auction was
+/// settled without delivering the parcel.
+pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+    let bid = &ctx.accounts.bid;
+    if let Some(auction) = Auction::load(&ctx.accounts.auction)? {
+        require!(auction.highest_bidder != Some(bid.bidder), Errors::BidStillLeading);
+    }
+
+    let bid_seeds: &[&[&[u8]]] = &[&[
+        Bid::SEED.as_bytes(),
+        bid.auction.as_ref(),
+        bid.bidder.as_ref(),
+        &[bid.bump]
+    ]];
+    let amount = ctx.accounts.bid_escrow.amount;
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.bid_escrow.to_account_info(),
+                to: ctx.accounts.bidder_token_account.to_account_info(),
+                authority: bid.to_account_info()
+            },
+            bid_seeds
+        ),
+        amount
+    )?;
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.bid_escrow.to_account_info(),
+                destination: ctx.accounts.bidder.to_account_info(),
+                authority: bid.to_account_info()
+            },
+            bid_seeds
+        )
+    )?;
+
+    emit!(BidWithdrawn {
+        auction: bid.auction,
+        bidder: bid.bidder,
+        amount,
+    });
+
+    Ok(())
+}
\ No newline at end of file
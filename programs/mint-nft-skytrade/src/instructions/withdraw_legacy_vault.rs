@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use mpl_bubblegum::types::LeafSchema;
+use mpl_bubblegum::utils::get_asset_id;
+use spl_account_compression::program::SplAccountCompression;
+use crate::constants::*;
+use crate::events::LegacyVaultWithdrawn;
+use crate::utils::verify_leaf;
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], data_hash: [u8; 32], creator_hash: [u8; 32], nonce: u64)]
+pub struct WithdrawLegacyVault<'info> {
+    /// the leaf owner, proven against the tree before the vault is released
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: only hashed into the leaf that is verified against the tree
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: the asset id of the leaf the vault was funded against
+    #[account(address = get_asset_id(&merkle_tree.key(), nonce))]
+    pub cnft: UncheckedAccount<'info>,
+
+    /// a vault from before deposits were keyed by mint and ledgered, which
+    /// is its own authority
+    #[account(
+        mut,
+        seeds = [constants::STAKE_VAULT, cnft.key().as_ref()],
+        bump,
+        token::authority = legacy_vault
+    )]
+    pub legacy_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = legacy_vault.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This account is checked in the downstream program
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub token_program: Program<'info, Token>
+}
+
+/// Pays out a vault funded before deposits were ledgered. Nothing records
+/// who funded it, so it goes to the parcel's owner, as burn used to do.
+pub fn withdraw_legacy_vault<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawLegacyVault<'info>>,
+                                    root: [u8; 32],
+                                    data_hash: [u8; 32],
+                                    creator_hash: [u8; 32],
+                                    nonce: u64,
+                                    index: u32) -> Result<()> {
+    let cnft = ctx.accounts.cnft.key();
+    let leaf = LeafSchema::V1 {
+        id: cnft,
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.leaf_delegate.key(),
+        nonce,
+        data_hash,
+        creator_hash,
+    };
+    verify_leaf(
+        &ctx.accounts.compression_program.to_account_info(),
+        &ctx.accounts.merkle_tree.to_account_info(),
+        ctx.remaining_accounts,
+        root,
+        &leaf,
+        index
+    )?;
+
+    let vault_seeds: &[&[&[u8]]] = &[&[constants::STAKE_VAULT, cnft.as_ref(), &[ctx.bumps.legacy_vault]]];
+    let amount = ctx.accounts.legacy_vault.amount;
+    if amount > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.legacy_vault.to_account_info(),
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: ctx.accounts.legacy_vault.to_account_info()
+                },
+                vault_seeds
+            ),
+            amount
+        )?;
+    }
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.legacy_vault.to_account_info(),
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: ctx.accounts.legacy_vault.to_account_info()
+            },
+            vault_seeds
+        )
+    )?;
+
+    emit!(LegacyVaultWithdrawn {
+        asset_id: cnft,
+        owner: ctx.accounts.owner.key(),
+        mint: ctx.accounts.legacy_vault.mint,
+        amount,
+    });
+
+    Ok(())
+}
\ No newline at end of file
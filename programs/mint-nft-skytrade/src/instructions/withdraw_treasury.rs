@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::events::TreasuryWithdrawn;
+use crate::state::{Config, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub treasurer: Signer<'info>,
+    #[account(
+        has_one = treasurer,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [Treasury::SEED.as_bytes(), treasury.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, treasury.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = treasury.mint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.record_spend(amount, Clock::get()?.unix_timestamp)?;
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.treasury_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: treasury.to_account_info()
+            },
+            &[&[
+                Treasury::SEED.as_bytes(),
+                treasury.mint.as_ref(),
+                &[treasury.bump]
+            ]]
+        ),
+        amount
+    )?;
+
+    emit!(TreasuryWithdrawn {
+        mint: treasury.mint,
+        treasurer: ctx.accounts.treasurer.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+
+    Ok(())
+}
\ No newline at end of file
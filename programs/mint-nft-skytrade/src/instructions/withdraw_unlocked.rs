@@ -0,0 +1,216 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, transfer, Transfer};
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::events::{PenaltyRecorded, UnlockWithdrawn};
+use crate::state::{Config, ParcelState, PenaltyKind, PenaltyRecord, PooledCustody, RewardPool, StakeInfo};
+
+#[derive(Accounts)]
+pub struct WithdrawUnlocked<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: checked against the stake info, gets its rent back
+    pub depositor: UncheckedAccount<'info>,
+
+    /// closed here unless rewards are still owed on it
+    #[account(
+        mut,
+        has_one = beneficiary,
+        has_one = depositor,
+        seeds = [StakeInfo::SEED.as_bytes(), stake_info.cnft.as_ref(), stake_info.depositor.as_ref(), stake_info.mint.as_ref()],
+        bump = stake_info.bump
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    #[account(
+        mut,
+        seeds = [ParcelState::SEED.as_bytes(), stake_info.cnft.as_ref()],
+        bump = parcel_state.bump
+    )]
+    pub parcel_state: Account<'info, ParcelState>,
+
+    #[account(
+        mut,
+        seeds = [constants::STAKE_VAULT, stake_info.cnft.as_ref(), stake_info.mint.as_ref()],
+        bump
+    )]
+    pub cnft_stake_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [PooledCustody::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump = pooled_custody.bump
+    )]
+    pub pooled_custody: Option<Box<Account<'info, PooledCustody>>>,
+
+    #[account(
+        mut,
+        seeds = [constants::POOLED_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub pooled_vault: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [constants::VAULT_AUTHORITY],
+        bump
+    )]
+    /// CHECK: signs for every stake vault, holds no data
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [RewardPool::SEED.as_bytes(), stake_info.mint.as_ref()],
+        bump
+    )]
+    /// CHECK: only initialized for mints with a reward pool
+    pub reward_pool: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = stake_info.mint,
+        token::authority = beneficiary
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED.as_bytes()],
+        bump = config.bump
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [constants::TREASURY_VAULT, stake_info.mint.as_ref()],
+        bump
+    )]
+    pub treasury_vault: Box<Account<'info, TokenAccount>>,
+
+    /// only needed when the withdrawal comes before the minimum lock duration
+    #[account(
+        init,
+        seeds = [PenaltyRecord::SEED.as_bytes(), config.penalties.to_le_bytes().as_ref()],
+        bump,
+        payer = beneficiary,
+        space = PenaltyRecord::SIZE
+    )]
+    pub penalty_record: Option<Box<Account<'info, PenaltyRecord>>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>
+}
+
+pub fn withdraw_unlocked(ctx: Context<WithdrawUnlocked>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.stake_info.check_unlock(now)?;
+    // settle the stake's rewards, which stay claimable after the withdrawal
+    if let Some(mut reward_pool) = RewardPool::load(&ctx.accounts.reward_pool)? {
+        reward_pool.update(now)?;
+        reward_pool.close_stake(&mut ctx.accounts.stake_info)?;
+        reward_pool.save(&ctx.accounts.reward_pool)?;
+    }
+    let stake_info = &ctx.accounts.stake_info;
+    ctx.accounts.parcel_state.record_unlock(stake_info.amount, true)?;
+
+    let vault_seeds: &[&[&[u8]]] = &[&[constants::VAULT_AUTHORITY, &[ctx.bumps.vault_authority]]];
+    let vault = match (stake_info.pooled, &ctx.accounts.cnft_stake_vault, &ctx.accounts.pooled_vault) {
+        (false, Some(vault), _) | (true, _, Some(vault)) => vault,
+        _ => return err!(Errors::CustodyMismatch)
+    };
+
+    // exiting before the minimum lock duration forfeits part of the deposit
+    let penalty = ctx.accounts.config.early_unlock_penalty(stake_info.amount, stake_info.locked_at, now)?;
+    require!((penalty > 0) == ctx.accounts.penalty_record.is_some(), Errors::PenaltyRecordMismatch);
+    if penalty > 0 {
+        let index = ctx.accounts.config.next_penalty()?;
+        let penalty_record = ctx.accounts.penalty_record.as_mut().ok_or(Errors::PenaltyRecordMismatch)?;
+        penalty_record.bump = ctx.bumps.penalty_record;
+        penalty_record.record(PenaltyKind::EarlyUnlock, stake_info, penalty, constants::EARLY_UNLOCK_REASON,
+            ctx.accounts.beneficiary.key())?;
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info()
+                },
+                vault_seeds
+            ),
+            penalty
+        )?;
+
+        emit!(PenaltyRecorded {
+            index,
+            kind: PenaltyKind::EarlyUnlock,
+            asset_id: stake_info.cnft,
+            depositor: stake_info.depositor,
+            mint: stake_info.mint,
+            amount: penalty,
+            reason_code: constants::EARLY_UNLOCK_REASON,
+            authority: penalty_record.authority,
+        });
+    }
+
+    // tokens sent to the per-cNFT vault outside lock_fund belong to no entry,
+    // so the last depositor out takes them and the vault can be closed
+    let close_vault = !stake_info.pooled
+        && (ctx.accounts.parcel_state.stakers == 0 || vault.amount == stake_info.amount);
+    let stray = if close_vault {
+        vault.amount.checked_sub(stake_info.amount).ok_or(Errors::MathOverflow)?
+    } else {
+        0
+    };
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info()
+            },
+            vault_seeds
+        ),
+        stake_info.amount - penalty + stray
+    )?;
+
+    // the cNFT stays alive; only an emptied vault of its own goes away
+    let mut vault_closed = false;
+    if stake_info.pooled {
+        let pooled_custody = ctx.accounts.pooled_custody.as_mut().ok_or(Errors::CustodyMismatch)?;
+        pooled_custody.record_withdrawal(stake_info.amount, true)?;
+        pooled_custody.reconcile(vault.amount.checked_sub(stake_info.amount).ok_or(Errors::MathOverflow)?)?;
+    } else if close_vault {
+        close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: ctx.accounts.beneficiary.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info()
+                },
+                vault_seeds
+            )
+        )?;
+        vault_closed = true;
+    }
+
+    emit!(UnlockWithdrawn {
+        asset_id: stake_info.cnft,
+        depositor: stake_info.depositor,
+        beneficiary: stake_info.beneficiary,
+        amount: stake_info.amount - penalty,
+        penalty,
+        vault_closed,
+    });
+
+    let stake_info = &mut ctx.accounts.stake_info;
+    stake_info.record_exit();
+    if !stake_info.has_rewards_owed() {
+        stake_info.close(ctx.accounts.depositor.to_account_info())?;
+    }
+
+    Ok(())
+}
\ No newline at end of file
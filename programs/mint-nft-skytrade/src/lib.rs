@@ -2,9 +2,12 @@ mod instructions;
 mod state;
 mod constants;
 mod errors;
+mod events;
+mod utils;
 
 use anchor_lang::prelude::*;
 use crate::instructions::*;
+use crate::state::{AssetValuation, CurrentLeaf, DutchPricing, FeeSchedule, LeaseTerms, ParcelMetadata, PenaltyPolicy, Sponsorship, SwapTerms};
 
 declare_id!("6J3F9s5R11gAg6CQUjYfTKDi2WStJ73nmrcfKfWTYWKY");
 
@@ -24,6 +27,10 @@ pub mod mint_nft_skytrade {
         instructions::init(ctx)
     }
 
+    pub fn init_config(ctx: Context<InitConfig>) -> Result<()> {
+        instructions::init_config(ctx)
+    }
+
     pub fn create_tree(ctx: Context<CreateTree>, max_depth: u32, max_buffer_size: u32) -> Result<()> {
         instructions::create_tree(ctx, max_depth, max_buffer_size)
     }
@@ -33,8 +40,9 @@ pub mod mint_nft_skytrade {
     data_hash: [u8; 32],
     creator_hash: [u8; 32],
     nonce: u64,
-    index: u32) -> Result<()> {
-        instructions::transfer_cnft(ctx, root, data_hash,creator_hash,nonce,index)
+    index: u32,
+    carry_deposit: bool) -> Result<()> {
+        instructions::transfer_cnft(ctx, root, data_hash,creator_hash,nonce,index,carry_deposit)
     }
 
     pub fn whitelist_token(ctx: Context<WhitelistToken>) -> Result<()> {
@@ -45,20 +53,262 @@ pub mod mint_nft_skytrade {
         instructions::delist_token(ctx)
     }
 
-    pub fn mint_cnft(ctx: Context<MintCNFT>,name: String,symbol: String, uri: String,seller_fee_basis_points: u16) -> Result<()> {
-        instructions::mint_cnft(ctx,name, symbol,uri,seller_fee_basis_points,)
+    pub fn mint_cnft(ctx: Context<MintCNFT>,request_id: [u8; 32],name: String,symbol: String, uri: String,seller_fee_basis_points: u16) -> Result<MintedParcel> {
+        instructions::mint_cnft(ctx,request_id,name, symbol,uri,seller_fee_basis_points,)
     }
 
     pub fn burn_cnft<'info>(ctx: Context<'_, '_, '_, 'info, BurnCNFT<'info>>,
                      root: [u8; 32],
-                     data_hash: [u8; 32],
+                     metadata: ParcelMetadata,
                      creator_hash: [u8; 32],
                      nonce: u64,
                      index: u32) -> Result<()> {
-        instructions::burn_cnft(ctx, root, data_hash, creator_hash, nonce, index)
+        instructions::burn_cnft(ctx, root, metadata, creator_hash, nonce, index)
+    }
+
+    pub fn lock_fund<'info>(ctx: Context<'_, '_, '_, 'info, LockFund<'info>>,
+                     root: [u8; 32],
+                     data_hash: [u8; 32],
+                     creator_hash: [u8; 32],
+                     nonce: u64,
+                     index: u32,
+                     sponsorship: Option<Sponsorship>) -> Result<()> {
+        instructions::lock_fund(ctx, root, data_hash, creator_hash, nonce, index, sponsorship)
+    }
+
+    pub fn withdraw_legacy_vault<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawLegacyVault<'info>>,
+                                        root: [u8; 32],
+                                        data_hash: [u8; 32],
+                                        creator_hash: [u8; 32],
+                                        nonce: u64,
+                                        index: u32) -> Result<()> {
+        instructions::withdraw_legacy_vault(ctx, root, data_hash, creator_hash, nonce, index)
+    }
+
+    pub fn init_collection(ctx: Context<InitCollection>,
+                           max_supply: Option<u64>,
+                           symbol: String,
+                           allowed_uri_schemes: u8) -> Result<()> {
+        instructions::init_collection(ctx, max_supply, symbol, allowed_uri_schemes)
+    }
+
+    pub fn update_collection(ctx: Context<UpdateCollection>, max_supply: Option<u64>, allowed_uri_schemes: u8) -> Result<()> {
+        instructions::update_collection(ctx, max_supply, allowed_uri_schemes)
+    }
+
+    pub fn update_parcel_metadata<'info>(ctx: Context<'_, '_, '_, 'info, UpdateParcelMetadata<'info>>,
+                                         root: [u8; 32],
+                                         nonce: u64,
+                                         index: u32,
+                                         current_metadata: ParcelMetadata,
+                                         name: Option<String>,
+                                         uri: Option<String>) -> Result<()> {
+        instructions::update_parcel_metadata(ctx, root, nonce, index, current_metadata, name, uri)
+    }
+
+    pub fn delegate_cnft<'info>(ctx: Context<'_, '_, '_, 'info, DelegateCNFT<'info>>,
+                                root: [u8; 32],
+                                data_hash: [u8; 32],
+                                creator_hash: [u8; 32],
+                                nonce: u64,
+                                index: u32) -> Result<()> {
+        instructions::delegate_cnft(ctx, root, data_hash, creator_hash, nonce, index)
+    }
+
+    pub fn revoke_cnft_delegate<'info>(ctx: Context<'_, '_, '_, 'info, RevokeCNFTDelegate<'info>>,
+                                       root: [u8; 32],
+                                       data_hash: [u8; 32],
+                                       creator_hash: [u8; 32],
+                                       nonce: u64,
+                                       index: u32) -> Result<()> {
+        instructions::revoke_cnft_delegate(ctx, root, data_hash, creator_hash, nonce, index)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, treasurer: Pubkey, fees: FeeSchedule, unlock_cooldown: i64) -> Result<()> {
+        instructions::update_config(ctx, treasurer, fees, unlock_cooldown)
+    }
+
+    pub fn list_parcel<'info>(ctx: Context<'_, '_, '_, 'info, ListParcel<'info>>,
+                              root: [u8; 32],
+                              nonce: u64,
+                              index: u32,
+                              metadata: ParcelMetadata,
+                              price: u64) -> Result<()> {
+        instructions::list_parcel(ctx, root, nonce, index, metadata, price)
+    }
+
+    pub fn update_listing(ctx: Context<UpdateListing>, price: u64) -> Result<()> {
+        instructions::update_listing(ctx, price)
+    }
+
+    pub fn cancel_listing<'info>(ctx: Context<'_, '_, '_, 'info, CancelListing<'info>>, root: Option<[u8; 32]>) -> Result<()> {
+        instructions::cancel_listing(ctx, root)
+    }
+
+    pub fn buy_parcel<'info>(ctx: Context<'_, '_, '_, 'info, BuyParcel<'info>>,
+                             root: [u8; 32],
+                             max_price: u64) -> Result<()> {
+        instructions::buy_parcel(ctx, root, max_price)
+    }
+
+    pub fn start_auction<'info>(ctx: Context<'_, '_, '_, 'info, StartAuction<'info>>,
+                                root: [u8; 32],
+                                nonce: u64,
+                                index: u32,
+                                metadata: ParcelMetadata,
+                                params: AuctionParams) -> Result<()> {
+        instructions::start_auction(ctx, root, nonce, index, metadata, params)
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        instructions::place_bid(ctx, amount)
+    }
+
+    pub fn settle_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
+                                 root: [u8; 32],
+                                 stale_leaf: Option<CurrentLeaf>) -> Result<()> {
+        instructions::settle_auction(ctx, root, stale_leaf)
+    }
+
+    pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+        instructions::withdraw_bid(ctx)
+    }
+
+    pub fn cancel_auction<'info>(ctx: Context<'_, '_, '_, 'info, CancelAuction<'info>>, root: Option<[u8; 32]>) -> Result<()> {
+        instructions::cancel_auction(ctx, root)
+    }
+
+    pub fn list_parcel_dutch<'info>(ctx: Context<'_, '_, '_, 'info, ListParcel<'info>>,
+                                    root: [u8; 32],
+                                    nonce: u64,
+                                    index: u32,
+                                    metadata: ParcelMetadata,
+                                    pricing: DutchPricing) -> Result<()> {
+        instructions::list_parcel_dutch(ctx, root, nonce, index, metadata, pricing)
+    }
+
+    pub fn make_offer(ctx: Context<MakeOffer>, asset_id: Pubkey, amount: u64, expires_at: i64) -> Result<()> {
+        instructions::make_offer(ctx, asset_id, amount, expires_at)
+    }
+
+    pub fn cancel_offer(ctx: Context<CloseOffer>) -> Result<()> {
+        instructions::cancel_offer(ctx)
+    }
+
+    pub fn expire_offer(ctx: Context<CloseOffer>) -> Result<()> {
+        instructions::expire_offer(ctx)
+    }
+
+    pub fn accept_offer<'info>(ctx: Context<'_, '_, '_, 'info, AcceptOffer<'info>>,
+                               root: [u8; 32],
+                               nonce: u64,
+                               index: u32,
+                               metadata: ParcelMetadata) -> Result<()> {
+        instructions::accept_offer(ctx, root, nonce, index, metadata)
+    }
+
+    pub fn create_lease<'info>(ctx: Context<'_, '_, '_, 'info, CreateLease<'info>>,
+                               root: [u8; 32],
+                               data_hash: [u8; 32],
+                               creator_hash: [u8; 32],
+                               nonce: u64,
+                               index: u32,
+                               terms: LeaseTerms) -> Result<()> {
+        instructions::create_lease(ctx, root, data_hash, creator_hash, nonce, index, terms)
+    }
+
+    pub fn claim_lease_rent(ctx: Context<ClaimLeaseRent>) -> Result<()> {
+        instructions::claim_lease_rent(ctx)
+    }
+
+    pub fn close_lease(ctx: Context<CloseLease>) -> Result<()> {
+        instructions::close_lease(ctx)
+    }
+
+    pub fn make_swap<'info>(ctx: Context<'_, '_, '_, 'info, MakeSwap<'info>>,
+                            root: [u8; 32],
+                            data_hash: [u8; 32],
+                            creator_hash: [u8; 32],
+                            nonce: u64,
+                            index: u32,
+                            terms: SwapTerms) -> Result<()> {
+        instructions::make_swap(ctx, root, data_hash, creator_hash, nonce, index, terms)
+    }
+
+    pub fn cancel_swap<'info>(ctx: Context<'_, '_, '_, 'info, CancelSwap<'info>>,
+                              root: Option<[u8; 32]>) -> Result<()> {
+        instructions::cancel_swap(ctx, root)
+    }
+
+    pub fn accept_swap<'info>(ctx: Context<'_, '_, '_, 'info, AcceptSwap<'info>>,
+                              root: [u8; 32],
+                              taker_leaf: Option<TakerLeaf>,
+                              maker_proof_len: u8) -> Result<()> {
+        instructions::accept_swap(ctx, root, taker_leaf, maker_proof_len)
+    }
+
+    pub fn configure_treasury(ctx: Context<ConfigureTreasury>, spending_limit: u64, period: i64) -> Result<()> {
+        instructions::configure_treasury(ctx, spending_limit, period)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)
+    }
+
+    pub fn request_unlock(ctx: Context<RequestUnlock>) -> Result<()> {
+        instructions::request_unlock(ctx)
+    }
+
+    pub fn withdraw_unlocked(ctx: Context<WithdrawUnlocked>) -> Result<()> {
+        instructions::withdraw_unlocked(ctx)
+    }
+
+    pub fn init_reward_pool(ctx: Context<InitRewardPool>, emission_rate: u64) -> Result<()> {
+        instructions::init_reward_pool(ctx, emission_rate)
+    }
+
+    pub fn update_reward_pool(ctx: Context<UpdateRewardPool>, emission_rate: u64) -> Result<()> {
+        instructions::update_reward_pool(ctx, emission_rate)
+    }
+
+    pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+        instructions::fund_reward_pool(ctx, amount)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::claim_rewards(ctx)
+    }
+
+    pub fn init_pooled_custody(ctx: Context<InitPooledCustody>) -> Result<()> {
+        instructions::init_pooled_custody(ctx)
+    }
+
+    pub fn reconcile_pooled_custody(ctx: Context<ReconcilePooledCustody>) -> Result<()> {
+        instructions::reconcile_pooled_custody(ctx)
+    }
+
+    pub fn top_up_lock(ctx: Context<TopUpLock>, amount: u64) -> Result<()> {
+        instructions::top_up_lock(ctx, amount)
+    }
+
+    pub fn partial_unlock(ctx: Context<PartialUnlock>, amount: u64) -> Result<()> {
+        instructions::partial_unlock(ctx, amount)
+    }
+
+    pub fn update_penalty_policy(ctx: Context<UpdatePenaltyPolicy>, arbitrator: Pubkey, penalty_policy: PenaltyPolicy) -> Result<()> {
+        instructions::update_penalty_policy(ctx, arbitrator, penalty_policy)
+    }
+
+    pub fn slash_deposit(ctx: Context<SlashDeposit>, amount: u64, reason_code: u16) -> Result<()> {
+        instructions::slash_deposit(ctx, amount, reason_code)
+    }
+
+    pub fn close_stale_listing<'info>(ctx: Context<'_, '_, '_, 'info, CloseStaleListing<'info>>,
+                                      current_leaf: CurrentLeaf) -> Result<()> {
+        instructions::close_stale_listing(ctx, current_leaf)
     }
 
-    pub fn lock_fund(ctx: Context<LockFund>) -> Result<()> {
-        instructions::lock_fund(ctx)
+    pub fn value_asset(ctx: Context<ValueAsset>, asset_id: Pubkey, valuation: AssetValuation) -> Result<()> {
+        instructions::value_asset(ctx, asset_id, valuation)
     }
 }
\ No newline at end of file
@@ -1,9 +1,15 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::Errors;
 
+/// Valuation of a parcel, written by the admin through `value_asset`.
 #[account]
+#[derive(InitSpace)]
 pub struct Asset {
     pub id: Pubkey,
+    #[max_len(32)]
     pub name: String,
+    #[max_len(200)]
     pub metadata_url: String,
     pub price: u64,
     pub last_updated: u64,
@@ -11,17 +17,46 @@ pub struct Asset {
     pub authority: Pubkey,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
 pub enum Reputation {
     Low,
     Medium,
     High,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AssetValuation {
+    pub name: String,
+    pub metadata_url: String,
+    pub price: u64,
+    pub reputation: Reputation,
+}
+
 impl Asset {
-    pub fn from_account_info(account_info: &AccountInfo) -> Self {
-        let asset_info_data = &mut &**account_info.try_borrow_data().unwrap();
-        let asset = Asset::try_deserialize(asset_info_data).unwrap();
-         asset
+    pub const SEED: &'static str = "asset";
+    pub const SIZE: usize = 8 + Asset::INIT_SPACE;
+
+    /// Reads the valuation of `asset_id`, rejecting accounts this program
+    /// doesn't own and valuations of any other asset. Only `value_asset`
+    /// creates program-owned `Asset` accounts.
+    pub fn load(account_info: &AccountInfo, asset_id: Pubkey) -> Result<Asset> {
+        require_keys_eq!(*account_info.owner, crate::ID, Errors::InvalidAssetInfo);
+        let asset = Asset::try_deserialize(&mut &account_info.try_borrow_data()?[..])
+            .map_err(|_| Errors::InvalidAssetInfo)?;
+        require_keys_eq!(asset.id, asset_id, Errors::InvalidAssetInfo);
+        Ok(asset)
     }
-}
\ No newline at end of file
+
+    pub fn set_valuation(&mut self, valuation: AssetValuation, authority: Pubkey, now: i64) -> Result<()> {
+        require!(valuation.name.len() <= constants::MAX_NAME_LENGTH, Errors::NameTooLong);
+        require!(valuation.metadata_url.len() <= constants::MAX_URI_LENGTH, Errors::UriTooLong);
+        require!(valuation.price > 0, Errors::InvalidPrice);
+        self.name = valuation.name;
+        self.metadata_url = valuation.metadata_url;
+        self.price = valuation.price;
+        self.reputation = valuation.reputation;
+        self.authority = authority;
+        self.last_updated = now as u64;
+        Ok(())
+    }
+}
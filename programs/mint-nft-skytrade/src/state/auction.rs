@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// An English auction of a parcel cNFT. Like a listing, the auction PDA is
+/// the leaf delegate until settlement. Each bidder escrows funds under
+/// their own Bid, and only the highest bid is paid out on settlement.
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub collection_mint: Pubkey,
+    pub royalty_bps: u16,
+    pub payment_mint: Pubkey,
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub anti_snipe_window: i64,
+    pub highest_bid: u64,
+    pub highest_bidder: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Auction {
+    pub const SEED: &'static str = "auction";
+    pub const SIZE: usize = 8 + Auction::INIT_SPACE;
+
+    pub fn load(account_info: &AccountInfo) -> Result<Option<Auction>> {
+        if account_info.data_is_empty() {
+            return Ok(None);
+        }
+        let auction = Auction::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        Ok(Some(auction))
+    }
+
+    pub fn min_next_bid(&self) -> Result<u64> {
+        match self.highest_bidder {
+            Some(_) => self.highest_bid.checked_add(self.min_increment).ok_or(Errors::MathOverflow.into()),
+            None => Ok(self.reserve_price),
+        }
+    }
+
+    /// Pushes the end out when a bid lands inside the anti-sniping window.
+    pub fn extend_if_sniping(&mut self, now: i64) -> Result<()> {
+        let remaining = self.end_time.checked_sub(now).ok_or(Errors::MathOverflow)?;
+        if remaining < self.anti_snipe_window {
+            self.end_time = now.checked_add(self.anti_snipe_window).ok_or(Errors::MathOverflow)?;
+        }
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// A bidder's standing bid on an auction. The funds sit in the bidder's own
+/// escrow owned by this PDA, so being outbid never pushes tokens anywhere;
+/// the bidder pulls them back with withdraw_bid once they no longer lead.
+#[account]
+#[derive(InitSpace)]
+pub struct Bid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl Bid {
+    pub const SEED: &'static str = "bid";
+    pub const SIZE: usize = 8 + Bid::INIT_SPACE;
+}
\ No newline at end of file
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::Errors;
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionConfig {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub max_supply: Option<u64>,
+    pub minted: u64,
+    pub burned: u64,
+    pub live: u64,
+    #[max_len(10)]
+    pub symbol: String,
+    pub allowed_uri_schemes: u8,
+    pub bump: u8,
+}
+
+impl CollectionConfig {
+    pub const SEED: &'static str = "collection_config";
+    pub const SIZE: usize = 8 + CollectionConfig::INIT_SPACE;
+
+    pub fn set_max_supply(&mut self, max_supply: Option<u64>) -> Result<()> {
+        if let Some(max_supply) = max_supply {
+            require!(max_supply >= self.minted, Errors::InvalidMaxSupply);
+        }
+        self.max_supply = max_supply;
+        Ok(())
+    }
+
+    pub fn set_symbol(&mut self, symbol: String) -> Result<()> {
+        require!(symbol.len() <= constants::MAX_SYMBOL_LENGTH, Errors::SymbolTooLong);
+        self.symbol = symbol;
+        Ok(())
+    }
+
+    pub fn set_allowed_uri_schemes(&mut self, allowed_uri_schemes: u8) -> Result<()> {
+        let known_schemes = constants::URI_SCHEMES.iter().fold(0, |mask, (flag, _)| mask | flag);
+        require!(allowed_uri_schemes != 0 && allowed_uri_schemes & !known_schemes == 0,
+            Errors::InvalidUriSchemes);
+        self.allowed_uri_schemes = allowed_uri_schemes;
+        Ok(())
+    }
+
+    pub fn validate_metadata(&self, name: &str, symbol: &str, uri: &str, seller_fee_basis_points: u16) -> Result<()> {
+        require!(!name.is_empty(), Errors::NameEmpty);
+        require!(name.len() <= constants::MAX_NAME_LENGTH, Errors::NameTooLong);
+        require!(symbol.len() <= constants::MAX_SYMBOL_LENGTH, Errors::SymbolTooLong);
+        require!(symbol == self.symbol, Errors::SymbolMismatch);
+        require!(uri.len() <= constants::MAX_URI_LENGTH, Errors::UriTooLong);
+        require!(constants::URI_SCHEMES.iter().any(|(flag, prefix)|
+            self.allowed_uri_schemes & flag != 0 && uri.starts_with(prefix)),
+            Errors::UriSchemeNotAllowed);
+        require!(seller_fee_basis_points <= constants::MAX_BASIS_POINTS, Errors::InvalidSellerFeeBasisPoints);
+        Ok(())
+    }
+
+    pub fn record_mint(&mut self) -> Result<()> {
+        if let Some(max_supply) = self.max_supply {
+            require!(self.minted < max_supply, Errors::MaxSupplyReached);
+        }
+        self.minted = self.minted.checked_add(1).ok_or(Errors::MathOverflow)?;
+        self.live = self.live.checked_add(1).ok_or(Errors::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_burn(&mut self) -> Result<()> {
+        self.live = self.live.checked_sub(1).ok_or(Errors::MathOverflow)?;
+        self.burned = self.burned.checked_add(1).ok_or(Errors::MathOverflow)?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::Errors;
+use crate::utils::bps_amount;
+
+/// Protocol fees in basis points, one per fee-bearing action. Fees are
+/// paid into the treasury of the mint the action settles in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeSchedule {
+    /// charged on the parcel's asset price when minting
+    pub mint_bps: u16,
+    /// charged on top of the amount locked through `lock_fund`
+    pub lock_bps: u16,
+    /// taken out of the price of listings, auctions and offers
+    pub sale_bps: u16,
+    /// taken out of the rent paid to lessors
+    pub lease_bps: u16,
+}
+
+/// Penalty for withdrawing a deposit before it has been locked for
+/// `min_lock_duration` seconds, paid into the mint's treasury.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PenaltyPolicy {
+    pub min_lock_duration: i64,
+    pub early_unlock_penalty_bps: u16,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasurer: Pubkey,
+    pub fees: FeeSchedule,
+    /// seconds between `request_unlock` and `withdraw_unlocked`
+    pub unlock_cooldown: i64,
+    /// may slash deposits after a dispute ruling
+    pub arbitrator: Pubkey,
+    pub penalty_policy: PenaltyPolicy,
+    /// number of `PenaltyRecord`s written so far
+    pub penalties: u64,
+    pub bump: u8,
+}
+
+impl FeeSchedule {
+    pub fn validate(&self) -> Result<()> {
+        require!([self.mint_bps, self.lock_bps, self.sale_bps, self.lease_bps]
+            .iter()
+            .all(|bps| *bps <= constants::MAX_BASIS_POINTS), Errors::InvalidFeeBasisPoints);
+        Ok(())
+    }
+}
+
+impl PenaltyPolicy {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.min_lock_duration >= 0
+            && self.early_unlock_penalty_bps <= constants::MAX_BASIS_POINTS, Errors::InvalidPenaltyPolicy);
+        Ok(())
+    }
+}
+
+impl Config {
+    pub const SEED: &'static str = "config";
+    pub const SIZE: usize = 8 + Config::INIT_SPACE;
+
+    pub fn set_fees(&mut self, fees: FeeSchedule) -> Result<()> {
+        fees.validate()?;
+        self.fees = fees;
+        Ok(())
+    }
+
+    pub fn set_unlock_cooldown(&mut self, unlock_cooldown: i64) -> Result<()> {
+        require!(unlock_cooldown >= 0, Errors::InvalidUnlockCooldown);
+        self.unlock_cooldown = unlock_cooldown;
+        Ok(())
+    }
+
+    pub fn set_penalty_policy(&mut self, penalty_policy: PenaltyPolicy) -> Result<()> {
+        penalty_policy.validate()?;
+        self.penalty_policy = penalty_policy;
+        Ok(())
+    }
+
+    /// What an exit at `now` forfeits of `amount` locked since `locked_at`.
+    pub fn early_unlock_penalty(&self, amount: u64, locked_at: i64, now: i64) -> Result<u64> {
+        let unlocked_at = locked_at
+            .checked_add(self.penalty_policy.min_lock_duration)
+            .ok_or(Errors::MathOverflow)?;
+        if now >= unlocked_at {
+            return Ok(0);
+        }
+        bps_amount(amount, self.penalty_policy.early_unlock_penalty_bps)
+    }
+
+    pub fn next_penalty(&mut self) -> Result<u64> {
+        let index = self.penalties;
+        self.penalties = self.penalties.checked_add(1).ok_or(Errors::MathOverflow)?;
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(min_lock_duration: i64, early_unlock_penalty_bps: u16) -> Config {
+        Config {
+            admin: Pubkey::default(),
+            treasurer: Pubkey::default(),
+            fees: FeeSchedule::default(),
+            unlock_cooldown: 0,
+            arbitrator: Pubkey::default(),
+            penalty_policy: PenaltyPolicy { min_lock_duration, early_unlock_penalty_bps },
+            penalties: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn early_unlock_penalty_applies_until_the_minimum_duration() {
+        let config = config(100, 500);
+        assert_eq!(config.early_unlock_penalty(1_000, 1_000, 1_000).unwrap(), 50);
+        assert_eq!(config.early_unlock_penalty(1_000, 1_000, 1_099).unwrap(), 50);
+        assert_eq!(config.early_unlock_penalty(1_000, 1_000, 1_100).unwrap(), 0);
+        assert_eq!(config.early_unlock_penalty(1_000, 1_000, 5_000).unwrap(), 0);
+        // rounds down, so dust amounts escape the penalty
+        assert_eq!(config.early_unlock_penalty(19, 1_000, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn early_unlock_penalty_is_off_by_default() {
+        assert_eq!(config(0, 500).early_unlock_penalty(1_000, 1_000, 1_000).unwrap(), 0);
+        assert_eq!(config(100, 0).early_unlock_penalty(1_000, 1_000, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn early_unlock_penalty_overflow_is_an_error() {
+        assert!(config(i64::MAX, 500).early_unlock_penalty(1_000, 1, 2).is_err());
+        assert_eq!(config(100, constants::MAX_BASIS_POINTS).early_unlock_penalty(u64::MAX, 0, 0).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn penalty_policy_validation() {
+        assert!(PenaltyPolicy { min_lock_duration: 0, early_unlock_penalty_bps: 10_000 }.validate().is_ok());
+        assert!(PenaltyPolicy { min_lock_duration: -1, early_unlock_penalty_bps: 0 }.validate().is_err());
+        assert!(PenaltyPolicy { min_lock_duration: 0, early_unlock_penalty_bps: 10_001 }.validate().is_err());
+    }
+}
\ No newline at end of file
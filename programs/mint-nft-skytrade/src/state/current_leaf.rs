@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::types::LeafSchema;
+
+/// The leaf an asset currently has in its tree. Proving it shows that a
+/// listing, auction or offer set up against an older leaf can no longer
+/// move the asset, so its accounts can be closed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CurrentLeaf {
+    pub root: [u8; 32],
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+}
+
+impl CurrentLeaf {
+    pub fn to_leaf_schema(self, asset_id: Pubkey, nonce: u64) -> LeafSchema {
+        LeafSchema::V1 {
+            id: asset_id,
+            owner: self.owner,
+            delegate: self.delegate,
+            nonce,
+            data_hash: self.data_hash,
+            creator_hash: self.creator_hash,
+        }
+    }
+}
\ No newline at end of file
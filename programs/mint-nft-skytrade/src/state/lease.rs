@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// A time-bound rental of a parcel's air rights. The lessee's full
+/// deposit is escrowed up front and streams to the owner per second.
+/// The lease pins the parcel through its ParcelState, which only this
+/// program's own instructions honour; the leaf itself is not frozen, so a
+/// direct bubblegum transfer still moves it.
+#[account]
+#[derive(InitSpace)]
+pub struct Lease {
+    pub owner: Pubkey,
+    pub lessee: Pubkey,
+    pub asset_id: Pubkey,
+    pub payment_mint: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub rate_per_second: u64,
+    pub deposit: u64,
+    pub claimed: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LeaseTerms {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub rate_per_second: u64,
+}
+
+impl Lease {
+    pub const SEED: &'static str = "lease";
+    pub const SIZE: usize = 8 + Lease::INIT_SPACE;
+
+    /// Rent earned by the owner from `start_time` up to `now`, capped at `end_time`.
+    pub fn accrued(&self, now: i64) -> Result<u64> {
+        let elapsed = now.clamp(self.start_time, self.end_time)
+            .checked_sub(self.start_time)
+            .ok_or(Errors::MathOverflow)?;
+        Ok(self.rate_per_second.checked_mul(elapsed as u64).ok_or(Errors::MathOverflow)?)
+    }
+
+    pub fn claimable(&self, now: i64) -> Result<u64> {
+        Ok(self.accrued(now)?.checked_sub(self.claimed).ok_or(Errors::MathOverflow)?)
+    }
+}
+
+impl LeaseTerms {
+    /// The deposit covering the whole term. Leases can't start in the past.
+    pub fn deposit(&self, now: i64) -> Result<u64> {
+        require!(self.rate_per_second > 0 && self.start_time >= now, Errors::InvalidLeaseTerms);
+        let duration = self.end_time.checked_sub(self.start_time).ok_or(Errors::MathOverflow)?;
+        require!(duration > 0, Errors::InvalidLeaseTerms);
+        Ok(self.rate_per_second.checked_mul(duration as u64).ok_or(Errors::MathOverflow)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(start_time: i64, end_time: i64, rate_per_second: u64) -> LeaseTerms {
+        LeaseTerms { start_time, end_time, rate_per_second }
+    }
+
+    #[test]
+    fn deposit_covers_the_whole_term() {
+        assert_eq!(terms(100, 200, 3).deposit(100).unwrap(), 300);
+        assert_eq!(terms(150, 151, 7).deposit(100).unwrap(), 7);
+    }
+
+    #[test]
+    fn deposit_rejects_bad_terms() {
+        // starts in the past
+        assert!(terms(99, 200, 3).deposit(100).is_err());
+        // empty or inverted term
+        assert!(terms(200, 200, 3).deposit(100).is_err());
+        assert!(terms(200, 150, 3).deposit(100).is_err());
+        // free lease
+        assert!(terms(100, 200, 0).deposit(100).is_err());
+        // overflowing duration and deposit
+        assert!(terms(0, i64::MAX, 1).deposit(0).is_ok());
+        assert!(terms(i64::MAX, i64::MIN, 1).deposit(i64::MIN).is_err());
+        assert!(terms(0, i64::MAX, 3).deposit(0).is_err());
+    }
+
+    #[test]
+    fn accrual_is_capped_by_the_term() {
+        let lease = Lease {
+            owner: Pubkey::default(),
+            lessee: Pubkey::default(),
+            asset_id: Pubkey::default(),
+            payment_mint: Pubkey::default(),
+            start_time: 100,
+            end_time: 200,
+            rate_per_second: 3,
+            deposit: 300,
+            claimed: 90,
+            bump: 0,
+        };
+        assert_eq!(lease.accrued(50).unwrap(), 0);
+        assert_eq!(lease.accrued(130).unwrap(), 90);
+        assert_eq!(lease.accrued(500).unwrap(), 300);
+        assert!(lease.claimable(120).is_err());
+        assert_eq!(lease.claimable(130).unwrap(), 0);
+        assert_eq!(lease.claimable(200).unwrap(), 210);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// A sale of a parcel cNFT, either at a fixed `price` or along a dutch
+/// auction curve. The listing PDA is the leaf delegate while the listing
+/// is open, so it can sign the transfer to the buyer.
+#[account]
+#[derive(InitSpace)]
+pub struct Listing {
+    pub seller: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub collection_mint: Pubkey,
+    pub royalty_bps: u16,
+    pub payment_mint: Pubkey,
+    pub price: u64,
+    pub dutch: Option<DutchPricing>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PriceCurve {
+    Linear,
+    /// the amount above the floor halves every `half_life` seconds
+    Exponential { half_life: i64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct DutchPricing {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub curve: PriceCurve,
+}
+
+impl Listing {
+    pub const SEED: &'static str = "listing";
+    pub const SIZE: usize = 8 + Listing::INIT_SPACE;
+
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        match &self.dutch {
+            Some(dutch) => dutch.price_at(now),
+            None => Ok(self.price),
+        }
+    }
+}
+
+impl DutchPricing {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.floor_price > 0 && self.start_price > self.floor_price, Errors::InvalidDutchPricing);
+        require!(self.end_time > self.start_time, Errors::InvalidDutchPricing);
+        if let PriceCurve::Exponential { half_life } = self.curve {
+            require!(half_life > 0, Errors::InvalidDutchPricing);
+        }
+        Ok(())
+    }
+
+    /// The price at `now`, falling from `start_price` at `start_time` to
+    /// `floor_price` at `end_time`.
+    pub fn price_at(&self, now: i64) -> Result<u64> {
+        if now <= self.start_time {
+            return Ok(self.start_price);
+        }
+        if now >= self.end_time {
+            return Ok(self.floor_price);
+        }
+
+        let excess = (self.start_price - self.floor_price) as u128;
+        let elapsed = (now - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let remaining_excess = match self.curve {
+            PriceCurve::Linear => excess - excess * elapsed / duration,
+            PriceCurve::Exponential { half_life } => {
+                // what the curve would still hold at end_time is taken off linearly,
+                // so the price reaches the floor at end_time instead of snapping to it
+                let half_life = half_life as u128;
+                let tail = DutchPricing::decayed(excess, duration, half_life);
+                DutchPricing::decayed(excess, elapsed, half_life) - tail * elapsed / duration
+            }
+        };
+
+        let price = (self.floor_price as u128)
+            .checked_add(remaining_excess)
+            .ok_or(Errors::MathOverflow)?;
+        u64::try_from(price).map_err(|_| Errors::MathOverflow.into())
+    }
+
+    /// `excess` halved once per full half-life, interpolated within the current one.
+    fn decayed(excess: u128, elapsed: u128, half_life: u128) -> u128 {
+        let halvings = elapsed / half_life;
+        if halvings >= 128 {
+            return 0;
+        }
+        let decayed = excess >> halvings;
+        decayed - (decayed / 2) * (elapsed % half_life) / half_life
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pricing(curve: PriceCurve) -> DutchPricing {
+        DutchPricing {
+            start_price: 1_100,
+            floor_price: 100,
+            start_time: 1_000,
+            end_time: 2_000,
+            curve,
+        }
+    }
+
+    #[test]
+    fn linear_falls_from_start_to_floor() {
+        let dutch = pricing(PriceCurve::Linear);
+        assert_eq!(dutch.price_at(0).unwrap(), 1_100);
+        assert_eq!(dutch.price_at(1_000).unwrap(), 1_100);
+        assert_eq!(dutch.price_at(1_250).unwrap(), 850);
+        assert_eq!(dutch.price_at(1_500).unwrap(), 600);
+        assert_eq!(dutch.price_at(1_999).unwrap(), 101);
+        assert_eq!(dutch.price_at(2_000).unwrap(), 100);
+        assert_eq!(dutch.price_at(5_000).unwrap(), 100);
+    }
+
+    #[test]
+    fn exponential_halves_the_excess_per_half_life() {
+        // ten half-lives fit, so the tail left at end_time rounds to nothing
+        let dutch = pricing(PriceCurve::Exponential { half_life: 100 });
+        assert_eq!(dutch.price_at(1_000).unwrap(), 1_100);
+        assert_eq!(dutch.price_at(1_100).unwrap(), 100 + 500);
+        assert_eq!(dutch.price_at(1_200).unwrap(), 100 + 250);
+        assert_eq!(dutch.price_at(1_150).unwrap(), 100 + 375);
+    }
+
+    #[test]
+    fn exponential_reaches_the_floor_at_end_time() {
+        // only one half-life fits, so half the excess is left to interpolate away
+        let dutch = pricing(PriceCurve::Exponential { half_life: 1_000 });
+        assert_eq!(dutch.price_at(1_000).unwrap(), 1_100);
+        assert_eq!(dutch.price_at(1_500).unwrap(), 100 + 750 - 250);
+        assert_eq!(dutch.price_at(1_999).unwrap(), 102);
+        assert_eq!(dutch.price_at(2_000).unwrap(), 100);
+        assert_eq!(dutch.price_at(3_000).unwrap(), 100);
+    }
+
+    #[test]
+    fn exponential_never_rises() {
+        for half_life in [1, 7, 100, 333, 1_000, 5_000] {
+            let dutch = pricing(PriceCurve::Exponential { half_life });
+            let mut last = dutch.price_at(1_000).unwrap();
+            for now in 1_001..=2_000 {
+                let price = dutch.price_at(now).unwrap();
+                assert!(price <= last && price >= 100, "half_life {half_life} at {now}");
+                last = price;
+            }
+            assert_eq!(last, 100);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_bad_curves() {
+        assert!(pricing(PriceCurve::Linear).validate().is_ok());
+        assert!(pricing(PriceCurve::Exponential { half_life: 0 }).validate().is_err());
+        let mut dutch = pricing(PriceCurve::Linear);
+        dutch.floor_price = dutch.start_price;
+        assert!(dutch.validate().is_err());
+        let mut dutch = pricing(PriceCurve::Linear);
+        dutch.end_time = dutch.start_time;
+        assert!(dutch.validate().is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct MintReceipt {
+    pub request_id: [u8; 32],
+    pub asset_id: Pubkey,
+    pub leaf_index: u32,
+    pub merkle_tree: Pubkey,
+    pub leaf_owner: Pubkey,
+    pub minted_at: i64,
+    pub bump: u8,
+}
+
+impl MintReceipt {
+    pub const SEED: &'static str = "mint_receipt";
+    pub const SIZE: usize = 8 + MintReceipt::INIT_SPACE;
+
+    pub fn is_processed(&self) -> bool {
+        self.minted_at != 0
+    }
+}
\ No newline at end of file
@@ -1,5 +1,39 @@
 mod asset;
 mod token_whitelist;
+mod collection_config;
+mod mint_receipt;
+mod parcel_metadata;
+mod parcel_state;
+mod config;
+mod listing;
+mod auction;
+mod bid;
+mod offer;
+mod lease;
+mod swap;
+mod treasury;
+mod stake_info;
+mod reward_pool;
+mod pooled_custody;
+mod penalty_record;
+mod current_leaf;
 
 pub use asset::*;
-pub use token_whitelist::*;
\ No newline at end of file
+pub use token_whitelist::*;
+pub use collection_config::*;
+pub use mint_receipt::*;
+pub use parcel_metadata::*;
+pub use parcel_state::*;
+pub use config::*;
+pub use listing::*;
+pub use auction::*;
+pub use bid::*;
+pub use offer::*;
+pub use lease::*;
+pub use swap::*;
+pub use treasury::*;
+pub use stake_info::*;
+pub use reward_pool::*;
+pub use pooled_custody::*;
+pub use penalty_record::*;
+pub use current_leaf::*;
\ No newline at end of file
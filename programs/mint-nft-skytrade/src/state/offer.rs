@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// An escrowed bid on a parcel cNFT, listed or not. The offered tokens sit
+/// in an escrow token account owned by the offer PDA until the offer is
+/// accepted, cancelled or expired.
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub buyer: Pubkey,
+    pub asset_id: Pubkey,
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl Offer {
+    pub const SEED: &'static str = "offer";
+    pub const SIZE: usize = 8 + Offer::INIT_SPACE;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
\ No newline at end of file
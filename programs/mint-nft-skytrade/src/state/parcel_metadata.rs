@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::types::{Collection, MetadataArgs, TokenProgramVersion, TokenStandard};
+
+/// The metadata fields a parcel leaf is minted with. Every other
+/// `MetadataArgs` field is fixed by `mint_cnft`, so this is enough to
+/// rebuild the exact leaf metadata bubblegum hashes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ParcelMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+}
+
+impl ParcelMetadata {
+    pub fn to_metadata_args(&self, collection_mint: Pubkey) -> MetadataArgs {
+        MetadataArgs {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+            creators: vec![],
+            seller_fee_basis_points: self.seller_fee_basis_points,
+            primary_sale_happened: self.primary_sale_happened,
+            is_mutable: self.is_mutable,
+            edition_nonce: Some(0),
+            uses: None,
+            collection: Some(Collection {
+                verified: true,
+                key: collection_mint,
+            }),
+            token_program_version: TokenProgramVersion::Original,
+            token_standard: Some(TokenStandard::NonFungible),
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// Per-cNFT record of what currently pins a parcel in place: funds locked
+/// through `lock_fund` and the end of any lease. It only exists once one
+/// of those has happened for the cNFT.
+///
+/// The pin is only honoured by this program's own transfer, marketplace
+/// and burn instructions. The leaf is neither delegated nor frozen, so the
+/// owner can still move it by calling bubblegum directly.
+#[account]
+#[derive(InitSpace)]
+pub struct ParcelState {
+    pub cnft: Pubkey,
+    pub locked_amount: u64,
+    /// number of live `StakeInfo` entries, all of which burn must refund
+    pub stakers: u32,
+    pub lease_end: i64,
+    pub bump: u8,
+}
+
+impl ParcelState {
+    pub const SEED: &'static str = "parcel_state";
+    pub const SIZE: usize = 8 + ParcelState::INIT_SPACE;
+
+    pub fn is_locked(&self) -> bool {
+        self.locked_amount > 0
+    }
+
+    pub fn is_leased(&self, now: i64) -> bool {
+        now < self.lease_end
+    }
+
+    /// A lease stays open after its term until `close_lease` settles its
+    /// escrow and resets `lease_end`.
+    pub fn has_open_lease(&self) -> bool {
+        self.lease_end != 0
+    }
+
+    pub fn check_transferable(&self, now: i64) -> Result<()> {
+        require!(!self.is_locked(), Errors::CnftLocked);
+        require!(!self.is_leased(now), Errors::LeaseActive);
+        Ok(())
+    }
+
+    /// Reads the parcel state held by `account_info`. An uninitialized
+    /// account means nothing was ever locked or leased.
+    pub fn load(account_info: &AccountInfo) -> Result<Option<ParcelState>> {
+        if account_info.data_is_empty() {
+            return Ok(None);
+        }
+        let parcel_state = ParcelState::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        Ok(Some(parcel_state))
+    }
+
+    pub fn is_transfer_blocked(account_info: &AccountInfo) -> Result<bool> {
+        let now = Clock::get()?.unix_timestamp;
+        Ok(ParcelState::load(account_info)?
+            .is_some_and(|parcel_state| parcel_state.check_transferable(now).is_err()))
+    }
+
+    pub fn ensure_transferable(account_info: &AccountInfo) -> Result<()> {
+        match ParcelState::load(account_info)? {
+            Some(parcel_state) => parcel_state.check_transferable(Clock::get()?.unix_timestamp),
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_lock(&mut self, amount: u64, new_staker: bool) -> Result<()> {
+        if new_staker {
+            self.stakers = self.stakers.checked_add(1).ok_or(Errors::MathOverflow)?;
+        }
+        self.locked_amount = self.locked_amount.checked_add(amount).ok_or(Errors::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_unlock(&mut self, amount: u64, staker_left: bool) -> Result<()> {
+        if staker_left {
+            self.stakers = self.stakers.checked_sub(1).ok_or(Errors::MathOverflow)?;
+        }
+        self.locked_amount = self.locked_amount.checked_sub(amount).ok_or(Errors::MathOverflow)?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::StakeInfo;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PenaltyKind {
+    /// withheld from a deposit withdrawn before the minimum lock duration
+    EarlyUnlock,
+    /// taken by the arbitrator after a dispute ruling
+    Slash,
+}
+
+/// Permanent record of funds taken out of a deposit into the treasury.
+/// Records are numbered by `Config::penalties`, so they outlive the stake
+/// entry they were taken from.
+#[account]
+#[derive(InitSpace)]
+pub struct PenaltyRecord {
+    pub kind: PenaltyKind,
+    pub asset_id: Pubkey,
+    pub depositor: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub authority: Pubkey,
+    pub recorded_at: i64,
+    pub bump: u8,
+}
+
+impl PenaltyRecord {
+    pub const SEED: &'static str = "penalty_record";
+    pub const SIZE: usize = 8 + PenaltyRecord::INIT_SPACE;
+
+    pub fn record(&mut self, kind: PenaltyKind, stake_info: &StakeInfo, amount: u64,
+        reason_code: u16, authority: Pubkey) -> Result<()> {
+        self.kind = kind;
+        self.asset_id = stake_info.cnft;
+        self.depositor = stake_info.depositor;
+        self.mint = stake_info.mint;
+        self.amount = amount;
+        self.reason_code = reason_code;
+        self.authority = authority;
+        self.recorded_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// Ledger for the single pooled vault of a whitelisted mint. Pooled stakes
+/// keep their per-cNFT balance in `StakeInfo`, so no token account is
+/// opened per cNFT; `recorded` is the sum of those balances and the vault
+/// must always hold at least that much.
+#[account]
+#[derive(InitSpace)]
+pub struct PooledCustody {
+    pub mint: Pubkey,
+    pub recorded: u64,
+    pub entries: u64,
+    pub bump: u8,
+}
+
+impl PooledCustody {
+    pub const SEED: &'static str = "pooled_custody";
+    pub const SIZE: usize = 8 + PooledCustody::INIT_SPACE;
+
+    pub fn record_deposit(&mut self, amount: u64, new_entry: bool) -> Result<()> {
+        self.recorded = self.recorded.checked_add(amount).ok_or(Errors::MathOverflow)?;
+        if new_entry {
+            self.entries = self.entries.checked_add(1).ok_or(Errors::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64, closed_entry: bool) -> Result<()> {
+        self.recorded = self.recorded.checked_sub(amount).ok_or(Errors::MathOverflow)?;
+        if closed_entry {
+            self.entries = self.entries.checked_sub(1).ok_or(Errors::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Tokens sent straight to the vault are surplus, but the vault may
+    /// never hold less than the ledger says it owes.
+    pub fn reconcile(&self, vault_balance: u64) -> Result<u64> {
+        require!(vault_balance >= self.recorded, Errors::PooledVaultOutOfBalance);
+        Ok(vault_balance - self.recorded)
+    }
+
+    /// Reads a ledger passed outside the accounts struct. Mints that never
+    /// opened a pooled vault leave the account uninitialized.
+    pub fn load(account_info: &AccountInfo) -> Result<Option<PooledCustody>> {
+        if account_info.data_is_empty() {
+            return Ok(None);
+        }
+        let pooled_custody = PooledCustody::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        Ok(Some(pooled_custody))
+    }
+
+    pub fn save(&self, account_info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])
+    }
+}
\ No newline at end of file
@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+use crate::state::StakeInfo;
+
+/// Scales `acc_reward_per_share` so small emissions over a large stake
+/// don't round down to zero.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Emits `reward_mint` tokens at `emission_rate` per second to everyone
+/// with funds locked in `stake_mint`, pro rata by amount and time.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub emission_rate: u64,
+    pub acc_reward_per_share: u128,
+    pub last_update: i64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+impl RewardPool {
+    pub const SEED: &'static str = "reward_pool";
+    pub const SIZE: usize = 8 + RewardPool::INIT_SPACE;
+
+    /// Accrues emissions since `last_update` into the accumulator.
+    pub fn update(&mut self, now: i64) -> Result<()> {
+        if now <= self.last_update {
+            return Ok(());
+        }
+        if self.total_staked > 0 {
+            let elapsed = (now - self.last_update) as u128;
+            let reward = elapsed
+                .checked_mul(self.emission_rate as u128)
+                .and_then(|reward| reward.checked_mul(REWARD_PRECISION))
+                .ok_or(Errors::MathOverflow)?;
+            self.acc_reward_per_share = self.acc_reward_per_share
+                .checked_add(reward / self.total_staked as u128)
+                .ok_or(Errors::MathOverflow)?;
+        }
+        self.last_update = now;
+        Ok(())
+    }
+
+    /// Settles what the stake earned at its previously counted amount,
+    /// then counts its current amount. Stakes made before the pool existed
+    /// join the pool here, earning only from this point on.
+    pub fn sync_stake(&mut self, stake_info: &mut StakeInfo) -> Result<()> {
+        self.count_stake(stake_info, stake_info.amount)
+    }
+
+    /// Settles a stake that is being withdrawn or burned and stops counting
+    /// it. What it earned stays in `pending_rewards` for claim_rewards.
+    pub fn close_stake(&mut self, stake_info: &mut StakeInfo) -> Result<()> {
+        self.count_stake(stake_info, 0)
+    }
+
+    fn count_stake(&mut self, stake_info: &mut StakeInfo, amount: u64) -> Result<()> {
+        let earned = self.reward_debt(stake_info.rewarded_amount)?
+            .checked_sub(stake_info.reward_debt)
+            .ok_or(Errors::MathOverflow)?;
+        stake_info.pending_rewards = stake_info.pending_rewards
+            .checked_add(u64::try_from(earned).map_err(|_| Errors::MathOverflow)?)
+            .ok_or(Errors::MathOverflow)?;
+        self.total_staked = self.total_staked
+            .checked_sub(stake_info.rewarded_amount)
+            .and_then(|total| total.checked_add(amount))
+            .ok_or(Errors::MathOverflow)?;
+        stake_info.rewarded_amount = amount;
+        stake_info.reward_debt = self.reward_debt(amount)?;
+        Ok(())
+    }
+
+    fn reward_debt(&self, amount: u64) -> Result<u128> {
+        Ok((amount as u128)
+            .checked_mul(self.acc_reward_per_share)
+            .ok_or(Errors::MathOverflow)?
+            / REWARD_PRECISION)
+    }
+
+    /// Reads the pool held by `account_info`. Mints without a reward pool
+    /// leave the account uninitialized.
+    pub fn load(account_info: &AccountInfo) -> Result<Option<RewardPool>> {
+        if account_info.data_is_empty() {
+            return Ok(None);
+        }
+        let reward_pool = RewardPool::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        Ok(Some(reward_pool))
+    }
+
+    pub fn save(&self, account_info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::RefundRule;
+
+    fn pool(emission_rate: u64) -> RewardPool {
+        RewardPool {
+            stake_mint: Pubkey::default(),
+            reward_mint: Pubkey::default(),
+            emission_rate,
+            acc_reward_per_share: 0,
+            last_update: 0,
+            total_staked: 0,
+            bump: 0,
+        }
+    }
+
+    fn stake(amount: u64) -> StakeInfo {
+        StakeInfo {
+            cnft: Pubkey::default(),
+            depositor: Pubkey::default(),
+            beneficiary: Pubkey::default(),
+            owner: Pubkey::default(),
+            refund_rule: RefundRule::Owner,
+            mint: Pubkey::default(),
+            amount,
+            open: true,
+            locked_at: 0,
+            asset_price: amount,
+            asset: Pubkey::default(),
+            unlock_available_at: 0,
+            rewarded_amount: 0,
+            reward_debt: 0,
+            pending_rewards: 0,
+            pooled: false,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn update_only_accrues_while_something_is_staked() {
+        let mut pool = pool(10);
+        pool.update(100).unwrap();
+        assert_eq!(pool.acc_reward_per_share, 0);
+        assert_eq!(pool.last_update, 100);
+
+        pool.total_staked = 1_000;
+        pool.update(150).unwrap();
+        assert_eq!(pool.acc_reward_per_share, 50 * 10 * REWARD_PRECISION / 1_000);
+        // time going backwards or standing still changes nothing
+        pool.update(120).unwrap();
+        pool.update(150).unwrap();
+        assert_eq!(pool.last_update, 150);
+        assert_eq!(pool.acc_reward_per_share, 50 * 10 * REWARD_PRECISION / 1_000);
+    }
+
+    #[test]
+    fn stakes_earn_pro_rata_by_amount_and_time() {
+        let mut pool = pool(100);
+        let mut alice = stake(300);
+        let mut bob = stake(100);
+        pool.sync_stake(&mut alice).unwrap();
+        pool.sync_stake(&mut bob).unwrap();
+        assert_eq!(pool.total_staked, 400);
+
+        pool.update(10).unwrap();
+        pool.sync_stake(&mut alice).unwrap();
+        pool.sync_stake(&mut bob).unwrap();
+        assert_eq!(alice.pending_rewards, 750);
+        assert_eq!(bob.pending_rewards, 250);
+
+        // a stake that joins later only earns from then on
+        let mut carol = stake(400);
+        pool.sync_stake(&mut carol).unwrap();
+        pool.update(20).unwrap();
+        pool.sync_stake(&mut alice).unwrap();
+        pool.sync_stake(&mut carol).unwrap();
+        assert_eq!(alice.pending_rewards, 750 + 375);
+        assert_eq!(carol.pending_rewards, 500);
+    }
+
+    #[test]
+    fn sync_stake_follows_amount_changes() {
+        let mut pool = pool(100);
+        let mut alice = stake(100);
+        pool.sync_stake(&mut alice).unwrap();
+        pool.update(10).unwrap();
+
+        alice.amount = 300;
+        pool.sync_stake(&mut alice).unwrap();
+        assert_eq!(pool.total_staked, 300);
+        assert_eq!(alice.rewarded_amount, 300);
+        assert_eq!(alice.pending_rewards, 1_000);
+    }
+
+    #[test]
+    fn close_stake_keeps_what_was_earned() {
+        let mut pool = pool(100);
+        let mut alice = stake(100);
+        let mut bob = stake(100);
+        pool.sync_stake(&mut alice).unwrap();
+        pool.sync_stake(&mut bob).unwrap();
+        pool.update(10).unwrap();
+
+        pool.close_stake(&mut alice).unwrap();
+        assert_eq!(alice.pending_rewards, 500);
+        assert_eq!(alice.rewarded_amount, 0);
+        assert_eq!(pool.total_staked, 100);
+
+        // the closed stake stops earning and bob gets the whole emission
+        pool.update(20).unwrap();
+        pool.sync_stake(&mut alice).unwrap();
+        pool.sync_stake(&mut bob).unwrap();
+        assert_eq!(alice.pending_rewards, 500);
+        assert_eq!(bob.pending_rewards, 500 + 1_000);
+    }
+}
\ No newline at end of file
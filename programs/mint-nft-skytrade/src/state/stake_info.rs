@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::Errors;
+
+/// Who a deposit is refunded to on unlock or burn.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RefundRule {
+    /// the sponsor who paid the deposit
+    Depositor,
+    /// whoever owns the parcel, following the cNFT when it is carried over
+    Owner,
+}
+
+/// Names the parcel owner a sponsor is locking funds for.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Sponsorship {
+    pub owner: Pubkey,
+    pub refund_rule: RefundRule,
+}
+
+/// Ledger entry for the funds one depositor has locked against a cNFT.
+/// `owner` is the parcel owner the deposit backs, which is the depositor
+/// unless the deposit was sponsored. `beneficiary` receives the refund on
+/// unlock or burn as `refund_rule` dictates.
+#[account]
+#[derive(InitSpace)]
+pub struct StakeInfo {
+    pub cnft: Pubkey,
+    pub depositor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub owner: Pubkey,
+    pub refund_rule: RefundRule,
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// set from lock_fund until the deposit is withdrawn, burned or slashed
+    /// away; only open entries count towards the parcel's stakers
+    pub open: bool,
+    /// when the entry was opened, which top-ups don't move
+    pub locked_at: i64,
+    pub asset_price: u64,
+    pub asset: Pubkey,
+    /// when a requested unlock may be withdrawn, zero if none is pending
+    pub unlock_available_at: i64,
+    /// the amount the reward pool currently counts for this stake
+    pub rewarded_amount: u64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    /// held in the mint's pooled vault rather than a per-cNFT vault
+    pub pooled: bool,
+    pub bump: u8,
+}
+
+impl StakeInfo {
+    pub const SEED: &'static str = "stake_info";
+    pub const SIZE: usize = 8 + StakeInfo::INIT_SPACE;
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the entry for a `lock_fund` deposit, starting its lock.
+    pub fn record_open(&mut self, now: i64) {
+        self.open = true;
+        self.locked_at = now;
+    }
+
+    /// Sets who the deposit backs and who gets it back. Without a
+    /// sponsorship the depositor backs their own parcel.
+    pub fn set_sponsorship(&mut self, depositor: Pubkey, sponsorship: Option<Sponsorship>) {
+        let Sponsorship { owner, refund_rule } = sponsorship.unwrap_or(Sponsorship {
+            owner: depositor,
+            refund_rule: RefundRule::Owner,
+        });
+        self.depositor = depositor;
+        self.owner = owner;
+        self.refund_rule = refund_rule;
+        self.beneficiary = match refund_rule {
+            RefundRule::Depositor => depositor,
+            RefundRule::Owner => owner,
+        };
+    }
+
+    /// Moves the deposit along with the cNFT. An owner-refunded deposit
+    /// now pays out to the new owner; a sponsor keeps its own refund.
+    pub fn carry_over(&mut self, to: Pubkey) {
+        self.owner = to;
+        if self.refund_rule == RefundRule::Owner {
+            self.beneficiary = to;
+        }
+    }
+
+    /// Books a `lock_fund` or `top_up_lock` deposit. The asset price and
+    /// asset always reflect the latest deposit, and a new deposit cancels
+    /// any pending unlock.
+    pub fn record_deposit(&mut self, amount: u64, asset_price: u64, asset: Pubkey) -> Result<()> {
+        self.amount = self.amount.checked_add(amount).ok_or(Errors::MathOverflow)?;
+        self.asset_price = asset_price;
+        self.asset = asset;
+        self.unlock_available_at = 0;
+        Ok(())
+    }
+
+    /// Takes part of the deposit out while leaving the entry open, using
+    /// up the unlock request; a full exit goes through `withdraw_unlocked`.
+    pub fn record_partial_unlock(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0 && amount < self.amount, Errors::InvalidLockAmount);
+        self.amount -= amount;
+        self.unlock_available_at = 0;
+        Ok(())
+    }
+
+    /// A deposit that is being drawn down stays at or above the asset's
+    /// current price.
+    pub fn check_minimum_deposit(&self, asset_price: u64) -> Result<()> {
+        require!(self.amount >= asset_price, Errors::BelowMinimumDeposit);
+        Ok(())
+    }
+
+    /// Takes `amount` out of the deposit on the arbitrator's ruling. A slash
+    /// that empties the deposit exits the entry as a withdrawal would.
+    pub fn record_slash(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0 && amount <= self.amount, Errors::InvalidLockAmount);
+        self.amount -= amount;
+        Ok(())
+    }
+
+    /// Empties the entry once its deposit was withdrawn, refunded or slashed
+    /// away. It is only kept around while rewards are still owed on it.
+    pub fn record_exit(&mut self) {
+        self.amount = 0;
+        self.open = false;
+        self.unlock_available_at = 0;
+    }
+
+    pub fn has_rewards_owed(&self) -> bool {
+        self.pending_rewards > 0
+    }
+
+    pub fn request_unlock(&mut self, now: i64, cooldown: i64) -> Result<i64> {
+        self.unlock_available_at = now.checked_add(cooldown).ok_or(Errors::MathOverflow)?;
+        Ok(self.unlock_available_at)
+    }
+
+    pub fn check_unlock(&self, now: i64) -> Result<()> {
+        require!(self.unlock_available_at != 0, Errors::UnlockNotRequested);
+        require!(now >= self.unlock_available_at, Errors::UnlockCooldownActive);
+        Ok(())
+    }
+
+    /// The token account holding this stake: the mint's pooled vault or
+    /// the per-cNFT vault.
+    pub fn vault_address(&self, program_id: &Pubkey) -> Pubkey {
+        let (vault, _) = if self.pooled {
+            Pubkey::find_program_address(&[constants::POOLED_VAULT, self.mint.as_ref()], program_id)
+        } else {
+            Pubkey::find_program_address(&[constants::STAKE_VAULT, self.cnft.as_ref(), self.mint.as_ref()], program_id)
+        };
+        vault
+    }
+
+    /// Reads a ledger entry passed outside the accounts struct, checking
+    /// that it is this program's entry for `cnft`.
+    pub fn load(account_info: &AccountInfo, cnft: &Pubkey, program_id: &Pubkey) -> Result<StakeInfo> {
+        require_keys_eq!(*account_info.owner, *program_id, Errors::InvalidRefundAccount);
+        let stake_info = StakeInfo::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(&[
+            StakeInfo::SEED.as_bytes(),
+            cnft.as_ref(),
+            stake_info.depositor.as_ref(),
+            stake_info.mint.as_ref(),
+            &[stake_info.bump]
+        ], program_id).map_err(|_| Errors::InvalidRefundAccount)?;
+        require_keys_eq!(account_info.key(), expected, Errors::InvalidRefundAccount);
+        Ok(stake_info)
+    }
+
+    pub fn save(&self, account_info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])
+    }
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// Tokens added to one side of a swap. When the maker pays, the amount
+/// is escrowed when the swap is made; when the taker pays, it is taken
+/// from the taker's account at settlement.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct SwapTopUp {
+    pub payment_mint: Pubkey,
+    pub amount: u64,
+    pub paid_by_maker: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SwapTerms {
+    pub taker: Pubkey,
+    pub requested_asset: Option<Pubkey>,
+    pub top_up: Option<SwapTopUp>,
+    pub expires_at: i64,
+}
+
+/// An OTC swap of the maker's parcel cNFT for the taker's cNFT and/or
+/// tokens. The swap PDA is the leaf delegate of the maker's cNFT so the
+/// taker can settle both legs in a single transaction.
+#[account]
+#[derive(InitSpace)]
+pub struct SwapOffer {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub requested_asset: Option<Pubkey>,
+    pub top_up: Option<SwapTopUp>,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl SwapTerms {
+    pub fn validate(&self, maker: Pubkey, now: i64) -> Result<()> {
+        require!(self.expires_at > now, Errors::InvalidExpiry);
+        require_keys_neq!(self.taker, maker, Errors::InvalidSwapTerms);
+        if let Some(top_up) = self.top_up {
+            require!(top_up.amount > 0, Errors::InvalidSwapTerms);
+        }
+        // the maker has to get something back for the cNFT
+        require!(self.requested_asset.is_some() || self.top_up.is_some_and(|top_up| !top_up.paid_by_maker),
+            Errors::InvalidSwapTerms);
+        Ok(())
+    }
+}
+
+impl SwapOffer {
+    pub const SEED: &'static str = "swap_offer";
+    pub const SIZE: usize = 8 + SwapOffer::INIT_SPACE;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn maker_top_up(&self) -> Option<SwapTopUp> {
+        self.top_up.filter(|top_up| top_up.paid_by_maker)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::errors::Errors;
+
+/// Protocol revenue for one whitelisted mint. Fees land in the treasury
+/// vault token account; the treasurer may withdraw at most
+/// `spending_limit` per `period` seconds.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub mint: Pubkey,
+    pub spending_limit: u64,
+    pub period: i64,
+    pub period_start: i64,
+    pub spent_in_period: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const SEED: &'static str = "treasury";
+    pub const SIZE: usize = 8 + Treasury::INIT_SPACE;
+
+    pub fn set_spending_limit(&mut self, spending_limit: u64, period: i64) -> Result<()> {
+        require!(period > 0, Errors::InvalidSpendingPeriod);
+        self.spending_limit = spending_limit;
+        self.period = period;
+        Ok(())
+    }
+
+    /// Starts a new period once the current one has elapsed, then books
+    /// `amount` against the limit.
+    pub fn record_spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now >= self.period_start.saturating_add(self.period) {
+            self.period_start = now;
+            self.spent_in_period = 0;
+        }
+        let spent = self.spent_in_period.checked_add(amount).ok_or(Errors::MathOverflow)?;
+        require!(spent <= self.spending_limit, Errors::SpendingLimitExceeded);
+        self.spent_in_period = spent;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::types::LeafSchema;
+use spl_account_compression::cpi::accounts::VerifyLeaf;
+use crate::constants::*;
+use crate::errors::Errors;
+
+/// Returns `bps` basis points of `amount`, rounded down.
+pub fn bps_amount(amount: u64, bps: u16) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(Errors::MathOverflow)?
+        / constants::MAX_BASIS_POINTS as u128;
+    u64::try_from(value).map_err(|_| Errors::MathOverflow.into())
+}
+
+/// Proves `leaf` sits at `index` of `merkle_tree` under `root`, for
+/// instructions that act on a cNFT without moving it through bubblegum.
+pub fn verify_leaf<'info>(compression_program: &AccountInfo<'info>,
+                          merkle_tree: &AccountInfo<'info>,
+                          proof: &[AccountInfo<'info>],
+                          root: [u8; 32],
+                          leaf: &LeafSchema,
+                          index: u32) -> Result<()> {
+    spl_account_compression::cpi::verify_leaf(
+        CpiContext::new(
+            compression_program.clone(),
+            VerifyLeaf {
+                merkle_tree: merkle_tree.clone()
+            }
+        ).with_remaining_accounts(proof.to_vec()),
+        root,
+        leaf.hash(),
+        index
+    )
+}
+
+/// Closes a program-owned account that is not part of the accounts struct,
+/// such as one passed through `remaining_accounts`.
+pub fn close_account_info<'info>(account: &AccountInfo<'info>, destination: &AccountInfo<'info>) -> Result<()> {
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? = destination.lamports()
+        .checked_add(lamports)
+        .ok_or(Errors::MathOverflow)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+    Ok(())
+}
\ No newline at end of file
@@ -31,6 +31,31 @@ import {
 import { assert } from 'chai';
 import { MintNftSkytrade } from '../target/types/mint_nft_skytrade';
 import { AssetExtractor } from '../utils/utils';
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from '@solana/spl-token';
+import {
+  Parcel,
+  ParcelTree,
+  airdrop,
+  assetInfoFor,
+  burnParcel,
+  createPaymentMint,
+  expectError,
+  loadKeypair,
+  lockFund,
+  programPda,
+  requestUnlock,
+  setConfig,
+  setPenaltyPolicy,
+  sleep,
+  stakeAccounts,
+  valueAsset,
+  withdrawUnlocked,
+} from '../utils/localnet';
 import { DasApiAsset } from '@metaplex-foundation/digital-asset-standard-api';
 import bs58 from 'bs58';
 
@@ -83,6 +108,8 @@ describe('mint-nft-skytrade', () => {
   };
 
   let collectionNft: CreateCompressedNftOutput;
+  let collectionConfig: PublicKey;
+  let config: PublicKey;
 
   before(async () => {
     // Create collection nft
@@ -102,6 +129,18 @@ describe('mint-nft-skytrade', () => {
       newUpdateAuthority: treeOwner,
     });
 
+    [collectionConfig] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('collection_config'),
+        collectionNft.mintAddress.toBuffer(),
+      ],
+      program.programId
+    );
+    [config] = PublicKey.findProgramAddressSync(
+      [Buffer.from('config')],
+      program.programId
+    );
+
     // instruction to create new account with required space for tree
     const allocTreeIx = await createAllocTreeIx(
       connection,
@@ -156,6 +195,30 @@ describe('mint-nft-skytrade', () => {
     assert.strictEqual(treeAccount.getMaxDepth(), maxDepthSizePair.maxDepth);
   });
 
+  it('Initializes the collection supply', async () => {
+    const maxSupply = new anchor.BN(10_000);
+    // https | ar:// | ipfs://
+    const allowedUriSchemes = 0b111;
+    await program.methods
+      .initCollection(maxSupply, 'KNA', allowedUriSchemes)
+      .accounts({
+        admin: wallet.publicKey,
+        config,
+        collectionConfig,
+        collectionMint: collectionNft.mintAddress,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    const config = await program.account.collectionConfig.fetch(
+      collectionConfig
+    );
+    assert.ok(config.maxSupply.eq(maxSupply));
+    assert.strictEqual(config.minted.toNumber(), 0);
+    assert.strictEqual(config.live.toNumber(), 0);
+    assert.strictEqual(config.symbol, 'KNA');
+  });
+
   it('Mints a cnft to an existing tree and collection', async () => {
     // NFT metadata
     const name = 'KONNA';
@@ -163,14 +226,28 @@ describe('mint-nft-skytrade', () => {
     const uri =
       'https://arweave.net/Apu1g7uhv52CMeQNfevoody9dVDmaWtQ3TklI6cbNRM';
     const sellerFeeBasisPoints = 0;
+    // retries of the same mint must reuse this id so they cannot double-mint
+    const requestId = Array.from(Keypair.generate().publicKey.toBytes());
+    const [mintReceipt] = PublicKey.findProgramAddressSync(
+      [Buffer.from('mint_receipt'), Buffer.from(requestId)],
+      program.programId
+    );
     const tx = await program.methods
-      .mintCnft(name, symbol, uri, sellerFeeBasisPoints)
+      .mintCnft(requestId, name, symbol, uri, sellerFeeBasisPoints)
       .accounts({
+        mintReceipt,
         treeConfig,
         leafOwner: wallet.publicKey,
         merkleTree: merkleTree.publicKey,
         centralAuthority: treeOwner,
         collectionMint: collectionNft.mintAddress,
+        collectionConfig,
+        config,
+        // no mint fee is configured, so the fee accounts are left out
+        assetInfo: null,
+        payerTokenAccount: null,
+        treasuryVault: null,
+        tokenProgram: null,
         collectionMetadata: collectionNft.metadataAddress,
         editionAccount: collectionNft.masterEditionAddress,
         bubblegumSigner,
@@ -182,6 +259,37 @@ describe('mint-nft-skytrade', () => {
       })
       .rpc({ commitment: 'confirmed' });
     console.log(`https://explorer.solana.com/tx/${tx}?cluster=devnet`);
+
+    const config = await program.account.collectionConfig.fetch(
+      collectionConfig
+    );
+    assert.strictEqual(config.minted.toNumber(), 1);
+    assert.strictEqual(config.live.toNumber(), 1);
+
+    // the asset id and leaf index come back as return data, no DAS lookup needed
+    const txInfo = await connection.getTransaction(tx, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0,
+    });
+    const [returnData] = txInfo.meta.returnData.data;
+    const minted = program.coder.types.decode(
+      'MintedParcel',
+      Buffer.from(returnData, 'base64')
+    );
+    const [expectedAssetId] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('asset', 'utf8'),
+        merkleTree.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, 'le', 8),
+      ],
+      new PublicKey(MPL_BUBBLEGUM_PROGRAM_ID)
+    );
+    assert.strictEqual(minted.leafIndex, 0);
+    assert.ok(minted.assetId.equals(expectedAssetId));
+
+    const receipt = await program.account.mintReceipt.fetch(mintReceipt);
+    assert.ok(receipt.assetId.equals(expectedAssetId));
+    assert.strictEqual(receipt.leafIndex, 0);
   });
 
   it('Transfer Cnft', async () => {
@@ -238,21 +346,2052 @@ describe('mint-nft-skytrade', () => {
       throw new Error('Wallet does not own this asset!');
     }
 
+    // only exists when funds are locked against the cnft
+    const [parcelState] = PublicKey.findProgramAddressSync(
+      [Buffer.from('parcel_state'), new PublicKey(assetId).toBuffer()],
+      program.programId
+    );
+
     // the leaf owner should be a public key not a pda
     const tx = await program.methods
-      .transferNft(root, dataHash, creatorHash, nonce, index)
+      .transferNft(root, dataHash, creatorHash, nonce, index, false)
       .accounts({
+        authority: wallet.publicKey,
         leafOwner: wallet.publicKey,
+        leafDelegate: asset.ownership.delegate
+          ? new PublicKey(asset.ownership.delegate)
+          : wallet.publicKey,
         merkleTree: tree,
+        // nothing is locked, so no stake info entries follow the proof
+        parcelState,
         newLeafOwner: receiver,
         treeAuthority,
         bubblegumProgram: MPL_BUBBLEGUM_PROGRAM_ID,
         compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
         logWrapper: SPL_NOOP_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .remainingAccounts(proofPathAsAccounts)
       .rpc();
     console.log('Transfer successful:', tx);
   });
 });
+
+// Runs against a local validator (`anchor test --provider.cluster localnet`)
+// on a tree whose leaves are mirrored locally, so no indexer is needed.
+describe('parcel flows on localnet', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = provider.wallet as anchor.Wallet;
+  const program = anchor.workspace.MintNftSkytrade as Program<MintNftSkytrade>;
+
+  const connection = provider.connection;
+  const pda = (...seeds: Buffer[]) => programPda(program, ...seeds);
+
+  const tree = new ParcelTree(
+    program,
+    loadKeypair('tests/fixtures/parcel-tree.json')
+  );
+  const seller = Keypair.generate();
+  const buyer = Keypair.generate();
+  const assetPrice = ParcelTree.assetPrice;
+  let mint: PublicKey;
+
+  const balance = async (owner: PublicKey) =>
+    Number(
+      (
+        await getAccount(
+          connection,
+          getAssociatedTokenAddressSync(mint, owner),
+          'confirmed'
+        )
+      ).amount
+    );
+  const tokenAccount = (owner: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, owner);
+
+  before(async () => {
+    await tree.init();
+    await airdrop(provider, seller.publicKey);
+    await airdrop(provider, buyer.publicKey);
+    ({ mint } = await createPaymentMint(
+      program,
+      [wallet.payer, seller, buyer],
+      100_000_000
+    ));
+  });
+
+  describe('parcel metadata', () => {
+    const updateMetadata = (
+      parcel: Parcel,
+      authority: Keypair,
+      name: string | null,
+      uri: string | null
+    ) => {
+      const leaf = tree.leaf(parcel);
+      return program.methods
+        .updateParcelMetadata(
+          leaf.root,
+          leaf.nonce,
+          leaf.index,
+          parcel.metadata,
+          name,
+          uri
+        )
+        .accounts({
+          authority: authority.publicKey,
+          collectionConfig: tree.collectionConfig,
+          treeConfig: tree.treeConfig,
+          centralAuthority: tree.treeOwner,
+          collectionMint: tree.collectionMint,
+          collectionMetadata: tree.collectionMetadata,
+          leafOwner: parcel.owner,
+          leafDelegate: parcel.delegate,
+          merkleTree: tree.merkleTree,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          bubblegumProgram: MPL_BUBBLEGUM_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([authority])
+        .rpc({ commitment: 'confirmed' });
+    };
+
+    it('Updates the uri of a minted parcel', async () => {
+      const parcel = await tree.mint(wallet.publicKey);
+      assert.isTrue(parcel.metadata.isMutable);
+      const uri = 'https://arweave.net/resurveyed-parcel';
+
+      await updateMetadata(parcel, wallet.payer, null, uri);
+      parcel.metadata = { ...parcel.metadata, uri };
+
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Only lets the collection authority update metadata', async () => {
+      const parcel = tree.parcels[tree.parcels.length - 1];
+      await expectError(
+        updateMetadata(parcel, Keypair.generate(), 'SQUATTED', null),
+        'ConstraintHasOne'
+      );
+    });
+  });
+
+  describe('leaf delegates', () => {
+    const manager = Keypair.generate();
+    let parcel: Parcel;
+
+    before(async () => {
+      parcel = await tree.mint(wallet.publicKey);
+    });
+
+    it('Delegates a parcel to a property manager', async () => {
+      const leaf = tree.leaf(parcel);
+      await program.methods
+        .delegateCnft(
+          leaf.root,
+          leaf.dataHash,
+          leaf.creatorHash,
+          leaf.nonce,
+          leaf.index
+        )
+        .accounts({
+          treeAuthority: tree.treeConfig,
+          leafOwner: wallet.publicKey,
+          previousLeafDelegate: parcel.delegate,
+          newLeafDelegate: manager.publicKey,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(leaf.proof)
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(parcel, manager.publicKey);
+
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Revokes the delegate back to the owner', async () => {
+      const leaf = tree.leaf(parcel);
+      await program.methods
+        .revokeCnftDelegate(
+          leaf.root,
+          leaf.dataHash,
+          leaf.creatorHash,
+          leaf.nonce,
+          leaf.index
+        )
+        .accounts({
+          treeAuthority: tree.treeConfig,
+          leafOwner: wallet.publicKey,
+          previousLeafDelegate: parcel.delegate,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(leaf.proof)
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(parcel, wallet.publicKey);
+
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('fixed-price listings', () => {
+    const price = 5_000_000;
+    // 2.5% of every sale goes to the treasury
+    const saleBps = 250;
+    let parcel: Parcel;
+    let listing: PublicKey;
+
+    const list = (listPrice: number) => {
+      const leaf = tree.leaf(parcel);
+      return program.methods
+        .listParcel(
+          leaf.root,
+          leaf.nonce,
+          leaf.index,
+          parcel.metadata,
+          new anchor.BN(listPrice)
+        )
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          collectionConfig: tree.collectionConfig,
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            parcel.assetId.toBuffer()
+          ),
+          auction: pda(Buffer.from('auction'), parcel.assetId.toBuffer()),
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          treeAuthority: tree.treeConfig,
+          previousLeafDelegate: parcel.delegate,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    };
+    const buy = (maxPrice: number) =>
+      program.methods
+        .buyParcel(tree.root(), new anchor.BN(maxPrice))
+        .accounts({
+          buyer: buyer.publicKey,
+          listing,
+          seller: seller.publicKey,
+          config: pda(Buffer.from('config')),
+          collectionConfig: tree.collectionConfig,
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          buyerTokenAccount: tokenAccount(buyer.publicKey),
+          sellerTokenAccount: tokenAccount(seller.publicKey),
+          royaltyTokenAccount: tokenAccount(wallet.publicKey),
+          treasuryVault: pda(Buffer.from('treasury_vault'), mint.toBuffer()),
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            parcel.assetId.toBuffer()
+          ),
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(tree.proof(parcel))
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' });
+
+    before(async () => {
+      await setConfig(program, {
+        mintBps: 0,
+        lockBps: 0,
+        saleBps,
+        leaseBps: 0,
+      });
+      parcel = await tree.mint(seller.publicKey);
+      listing = pda(Buffer.from('listing'), parcel.assetId.toBuffer());
+    });
+
+    it('Lists a parcel by delegating its leaf to the listing', async () => {
+      await list(price);
+      tree.setDelegate(parcel, listing);
+
+      const listed = await program.account.listing.fetch(listing, 'confirmed');
+      assert.ok(listed.seller.equals(seller.publicKey));
+      assert.ok(listed.assetId.equals(parcel.assetId));
+      assert.ok(listed.paymentMint.equals(mint));
+      assert.strictEqual(listed.price.toNumber(), price);
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Updates the listing price', async () => {
+      await program.methods
+        .updateListing(new anchor.BN(2 * price))
+        .accounts({ seller: seller.publicKey, listing })
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+
+      const listed = await program.account.listing.fetch(listing, 'confirmed');
+      assert.strictEqual(listed.price.toNumber(), 2 * price);
+    });
+
+    it('Cancels a listing and hands the delegate back', async () => {
+      await program.methods
+        .cancelListing(tree.root())
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(tree.proof(parcel))
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(parcel, seller.publicKey);
+
+      assert.isNull(await connection.getAccountInfo(listing, 'confirmed'));
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Rejects a purchase above the buyer\'s max price', async () => {
+      await list(price);
+      tree.setDelegate(parcel, listing);
+
+      await expectError(buy(price - 1), 'PriceAboveMax');
+    });
+
+    it('Sells the parcel and pays out the seller and the fee', async () => {
+      const treasuryVault = pda(Buffer.from('treasury_vault'), mint.toBuffer());
+      const treasuryBefore = Number(
+        (await getAccount(connection, treasuryVault, 'confirmed')).amount
+      );
+      const sellerBefore = await balance(seller.publicKey);
+      const buyerBefore = await balance(buyer.publicKey);
+
+      await buy(price);
+      tree.setOwner(parcel, buyer.publicKey);
+
+      const fee = (price * saleBps) / 10_000;
+      assert.strictEqual(buyerBefore - (await balance(buyer.publicKey)), price);
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        price - fee
+      );
+      assert.strictEqual(
+        Number(
+          (await getAccount(connection, treasuryVault, 'confirmed')).amount
+        ) - treasuryBefore,
+        fee
+      );
+      assert.isNull(await connection.getAccountInfo(listing, 'confirmed'));
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('english auctions', () => {
+    const reservePrice = 4_000_000;
+    const minIncrement = 500_000;
+    const saleBps = 250;
+    let parcel: Parcel;
+    let auction: PublicKey;
+
+    const bidAccounts = (bidder: PublicKey) => {
+      const bid = pda(
+        Buffer.from('bid'),
+        auction.toBuffer(),
+        bidder.toBuffer()
+      );
+      return {
+        bid,
+        bidEscrow: pda(Buffer.from('bid_escrow'), bid.toBuffer()),
+      };
+    };
+    const start = (auctioned: Parcel, duration: number) => {
+      const leaf = tree.leaf(auctioned);
+      return program.methods
+        .startAuction(leaf.root, leaf.nonce, leaf.index, auctioned.metadata, {
+          reservePrice: new anchor.BN(reservePrice),
+          minIncrement: new anchor.BN(minIncrement),
+          duration: new anchor.BN(duration),
+          antiSnipeWindow: new anchor.BN(0),
+        })
+        .accounts({
+          seller: seller.publicKey,
+          auction: pda(Buffer.from('auction'), auctioned.assetId.toBuffer()),
+          collectionConfig: tree.collectionConfig,
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            auctioned.assetId.toBuffer()
+          ),
+          listing: pda(Buffer.from('listing'), auctioned.assetId.toBuffer()),
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          treeAuthority: tree.treeConfig,
+          previousLeafDelegate: auctioned.delegate,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    };
+    const placeBid = (bidder: Keypair, amount: number) =>
+      program.methods
+        .placeBid(new anchor.BN(amount))
+        .accounts({
+          bidder: bidder.publicKey,
+          auction,
+          ...bidAccounts(bidder.publicKey),
+          paymentMint: mint,
+          bidderTokenAccount: tokenAccount(bidder.publicKey),
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([bidder])
+        .rpc({ commitment: 'confirmed' });
+    const settle = (winner: PublicKey) =>
+      program.methods
+        .settleAuction(tree.root(), null)
+        .accounts({
+          auction,
+          seller: seller.publicKey,
+          winner,
+          winningBid: bidAccounts(wallet.publicKey).bid,
+          winningBidEscrow: bidAccounts(wallet.publicKey).bidEscrow,
+          config: pda(Buffer.from('config')),
+          collectionConfig: tree.collectionConfig,
+          paymentMint: mint,
+          sellerTokenAccount: tokenAccount(seller.publicKey),
+          royaltyTokenAccount: tokenAccount(wallet.publicKey),
+          treasuryVault: pda(Buffer.from('treasury_vault'), mint.toBuffer()),
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            parcel.assetId.toBuffer()
+          ),
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(tree.proof(parcel))
+        .rpc({ commitment: 'confirmed' });
+    const withdrawBid = (bidder: Keypair) =>
+      program.methods
+        .withdrawBid()
+        .accounts({
+          bidder: bidder.publicKey,
+          ...bidAccounts(bidder.publicKey),
+          auction,
+          bidderTokenAccount: tokenAccount(bidder.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([bidder])
+        .rpc({ commitment: 'confirmed' });
+
+    before(async () => {
+      await setConfig(program, {
+        mintBps: 0,
+        lockBps: 0,
+        saleBps,
+        leaseBps: 0,
+      });
+      parcel = await tree.mint(seller.publicKey);
+      auction = pda(Buffer.from('auction'), parcel.assetId.toBuffer());
+    });
+
+    it('Starts an auction by delegating the leaf to it', async () => {
+      await start(parcel, 10);
+      tree.setDelegate(parcel, auction);
+
+      const started = await program.account.auction.fetch(auction, 'confirmed');
+      assert.ok(started.seller.equals(seller.publicKey));
+      assert.strictEqual(started.reservePrice.toNumber(), reservePrice);
+      assert.isNull(started.highestBidder);
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Rejects a bid below the reserve price', async () => {
+      await expectError(placeBid(buyer, reservePrice - 1), 'BidTooLow');
+    });
+
+    it('Rejects listing the parcel while it is auctioned', async () => {
+      const leaf = tree.leaf(parcel);
+      await expectError(
+        program.methods
+          .listParcel(
+            leaf.root,
+            leaf.nonce,
+            leaf.index,
+            parcel.metadata,
+            new anchor.BN(reservePrice)
+          )
+          .accounts({
+            seller: seller.publicKey,
+            listing: pda(Buffer.from('listing'), parcel.assetId.toBuffer()),
+            collectionConfig: tree.collectionConfig,
+            parcelState: pda(
+              Buffer.from('parcel_state'),
+              parcel.assetId.toBuffer()
+            ),
+            auction,
+            whitelist: pda(Buffer.from('token_whitelist')),
+            paymentMint: mint,
+            treeAuthority: tree.treeConfig,
+            previousLeafDelegate: parcel.delegate,
+            ...tree.treeAccounts(),
+          })
+          .remainingAccounts(leaf.proof)
+          .signers([seller])
+          .rpc(),
+        'ParcelOnSale'
+      );
+    });
+
+    it('Escrows each bid in its own account', async () => {
+      await placeBid(buyer, reservePrice);
+      await expectError(
+        placeBid(wallet.payer, reservePrice + minIncrement - 1),
+        'BidTooLow'
+      );
+      await placeBid(wallet.payer, reservePrice + minIncrement);
+
+      const escrowed = async (bidder: PublicKey) =>
+        Number(
+          (
+            await getAccount(
+              connection,
+              bidAccounts(bidder).bidEscrow,
+              'confirmed'
+            )
+          ).amount
+        );
+      assert.strictEqual(await escrowed(buyer.publicKey), reservePrice);
+      assert.strictEqual(
+        await escrowed(wallet.publicKey),
+        reservePrice + minIncrement
+      );
+      const leading = await program.account.auction.fetch(auction, 'confirmed');
+      assert.ok(leading.highestBidder.equals(wallet.publicKey));
+    });
+
+    it('Lets only outbid bidders withdraw their bid', async () => {
+      await expectError(withdrawBid(wallet.payer), 'BidStillLeading');
+
+      const before = await balance(buyer.publicKey);
+      await withdrawBid(buyer);
+      assert.strictEqual(
+        (await balance(buyer.publicKey)) - before,
+        reservePrice
+      );
+      assert.isNull(
+        await connection.getAccountInfo(
+          bidAccounts(buyer.publicKey).bid,
+          'confirmed'
+        )
+      );
+    });
+
+    it('Settles the auction to the highest bidder', async () => {
+      const { endTime } = await program.account.auction.fetch(
+        auction,
+        'confirmed'
+      );
+      await sleep(Math.max(0, endTime.toNumber() - Date.now() / 1000) + 2);
+      const sellerBefore = await balance(seller.publicKey);
+
+      await expectError(settle(buyer.publicKey), 'NotAuctionWinner');
+      await settle(wallet.publicKey);
+      tree.setOwner(parcel, wallet.publicKey);
+
+      const price = reservePrice + minIncrement;
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        price - (price * saleBps) / 10_000
+      );
+      assert.isNull(await connection.getAccountInfo(auction, 'confirmed'));
+      assert.isNull(
+        await connection.getAccountInfo(
+          bidAccounts(wallet.publicKey).bidEscrow,
+          'confirmed'
+        )
+      );
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Cancels an auction that drew no bids', async () => {
+      const unsold = await tree.mint(seller.publicKey);
+      const unsoldAuction = pda(
+        Buffer.from('auction'),
+        unsold.assetId.toBuffer()
+      );
+      await start(unsold, 60);
+      tree.setDelegate(unsold, unsoldAuction);
+
+      await program.methods
+        .cancelAuction(tree.root())
+        .accounts({
+          seller: seller.publicKey,
+          auction: unsoldAuction,
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(tree.proof(unsold))
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(unsold, seller.publicKey);
+
+      assert.isNull(
+        await connection.getAccountInfo(unsoldAuction, 'confirmed')
+      );
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('dutch listings', () => {
+    const startPrice = 8_000_000;
+    const floorPrice = 2_000_000;
+    const saleBps = 250;
+
+    const listingFor = (listed: Parcel) =>
+      pda(Buffer.from('listing'), listed.assetId.toBuffer());
+    const listDutch = (
+      listed: Parcel,
+      window: number,
+      curve: object = { linear: {} }
+    ) => {
+      const leaf = tree.leaf(listed);
+      const now = Math.floor(Date.now() / 1000);
+      return program.methods
+        .listParcelDutch(
+          leaf.root,
+          leaf.nonce,
+          leaf.index,
+          listed.metadata,
+          {
+            startPrice: new anchor.BN(startPrice),
+            floorPrice: new anchor.BN(floorPrice),
+            startTime: new anchor.BN(now),
+            endTime: new anchor.BN(now + window),
+            curve,
+          }
+        )
+        .accounts({
+          seller: seller.publicKey,
+          listing: listingFor(listed),
+          collectionConfig: tree.collectionConfig,
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            listed.assetId.toBuffer()
+          ),
+          auction: pda(Buffer.from('auction'), listed.assetId.toBuffer()),
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          treeAuthority: tree.treeConfig,
+          previousLeafDelegate: listed.delegate,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    };
+    const buy = (listed: Parcel, maxPrice: number) =>
+      program.methods
+        .buyParcel(tree.root(), new anchor.BN(maxPrice))
+        .accounts({
+          buyer: buyer.publicKey,
+          listing: listingFor(listed),
+          seller: seller.publicKey,
+          config: pda(Buffer.from('config')),
+          collectionConfig: tree.collectionConfig,
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          buyerTokenAccount: tokenAccount(buyer.publicKey),
+          sellerTokenAccount: tokenAccount(seller.publicKey),
+          royaltyTokenAccount: tokenAccount(wallet.publicKey),
+          treasuryVault: pda(Buffer.from('treasury_vault'), mint.toBuffer()),
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            listed.assetId.toBuffer()
+          ),
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(tree.proof(listed))
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' });
+
+    before(async () => {
+      await setConfig(program, {
+        mintBps: 0,
+        lockBps: 0,
+        saleBps,
+        leaseBps: 0,
+      });
+    });
+
+    it('Sells a linear dutch listing at the floor once its window ends', async () => {
+      const parcel = await tree.mint(seller.publicKey);
+      const listing = listingFor(parcel);
+      await listDutch(parcel, 8);
+      tree.setDelegate(parcel, listing);
+
+      const listed = await program.account.listing.fetch(listing, 'confirmed');
+      assert.strictEqual(listed.dutch.startPrice.toNumber(), startPrice);
+      assert.strictEqual(listed.dutch.floorPrice.toNumber(), floorPrice);
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+
+      await expectError(
+        program.methods
+          .updateListing(new anchor.BN(floorPrice))
+          .accounts({ seller: seller.publicKey, listing })
+          .signers([seller])
+          .rpc(),
+        'DutchListingNotUpdatable'
+      );
+      // still well above the floor this early in the window
+      await expectError(buy(parcel, floorPrice), 'PriceAboveMax');
+
+      await sleep(
+        Math.max(0, listed.dutch.endTime.toNumber() - Date.now() / 1000) + 2
+      );
+      const sellerBefore = await balance(seller.publicKey);
+      const buyerBefore = await balance(buyer.publicKey);
+
+      await buy(parcel, floorPrice);
+      tree.setOwner(parcel, buyer.publicKey);
+
+      const fee = (floorPrice * saleBps) / 10_000;
+      assert.strictEqual(
+        buyerBefore - (await balance(buyer.publicKey)),
+        floorPrice
+      );
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        floorPrice - fee
+      );
+      assert.isNull(await connection.getAccountInfo(listing, 'confirmed'));
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Charges the current price along an exponential curve', async () => {
+      const parcel = await tree.mint(seller.publicKey);
+      await listDutch(parcel, 600, {
+        exponential: { halfLife: new anchor.BN(60) },
+      });
+      tree.setDelegate(parcel, listingFor(parcel));
+      const buyerBefore = await balance(buyer.publicKey);
+
+      await buy(parcel, startPrice);
+      tree.setOwner(parcel, buyer.publicKey);
+
+      const paid = buyerBefore - (await balance(buyer.publicKey));
+      assert.isAbove(paid, floorPrice);
+      assert.isAtMost(paid, startPrice);
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('offers', () => {
+    const amount = 3_000_000;
+    const saleBps = 250;
+    let parcel: Parcel;
+    let offer: PublicKey;
+    let offerEscrow: PublicKey;
+
+    const makeOffer = (expiresIn: number) =>
+      program.methods
+        .makeOffer(
+          parcel.assetId,
+          new anchor.BN(amount),
+          new anchor.BN(Math.floor(Date.now() / 1000) + expiresIn)
+        )
+        .accounts({
+          buyer: buyer.publicKey,
+          offer,
+          offerEscrow,
+          buyerTokenAccount: tokenAccount(buyer.publicKey),
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' });
+    const closeAccounts = (signer: PublicKey) => ({
+      signer,
+      offer,
+      offerEscrow,
+      buyer: buyer.publicKey,
+      buyerTokenAccount: tokenAccount(buyer.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+    const accept = () => {
+      const leaf = tree.leaf(parcel);
+      return program.methods
+        .acceptOffer(leaf.root, leaf.nonce, leaf.index, parcel.metadata)
+        .accounts({
+          seller: seller.publicKey,
+          offer,
+          offerEscrow,
+          buyer: buyer.publicKey,
+          leafDelegate: parcel.delegate,
+          config: pda(Buffer.from('config')),
+          collectionConfig: tree.collectionConfig,
+          paymentMint: mint,
+          sellerTokenAccount: tokenAccount(seller.publicKey),
+          royaltyTokenAccount: tokenAccount(wallet.publicKey),
+          treasuryVault: pda(Buffer.from('treasury_vault'), mint.toBuffer()),
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            parcel.assetId.toBuffer()
+          ),
+          listing: pda(Buffer.from('listing'), parcel.assetId.toBuffer()),
+          auction: pda(Buffer.from('auction'), parcel.assetId.toBuffer()),
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    };
+
+    before(async () => {
+      await setConfig(program, {
+        mintBps: 0,
+        lockBps: 0,
+        saleBps,
+        leaseBps: 0,
+      });
+      parcel = await tree.mint(seller.publicKey);
+      offer = pda(
+        Buffer.from('offer'),
+        parcel.assetId.toBuffer(),
+        buyer.publicKey.toBuffer()
+      );
+      offerEscrow = pda(Buffer.from('offer_escrow'), offer.toBuffer());
+    });
+
+    it('Escrows an offer and refunds it when the buyer cancels', async () => {
+      const buyerBefore = await balance(buyer.publicKey);
+      await makeOffer(600);
+
+      const made = await program.account.offer.fetch(offer, 'confirmed');
+      assert.ok(made.assetId.equals(parcel.assetId));
+      assert.strictEqual(made.amount.toNumber(), amount);
+      assert.strictEqual(
+        Number((await getAccount(connection, offerEscrow, 'confirmed')).amount),
+        amount
+      );
+
+      await expectError(
+        program.methods
+          .cancelOffer()
+          .accounts(closeAccounts(seller.publicKey))
+          .signers([seller])
+          .rpc(),
+        'RequireKeysEqViolated'
+      );
+      // tokens sent to the escrow must not keep it from closing
+      await mintTo(
+        connection,
+        wallet.payer,
+        mint,
+        offerEscrow,
+        wallet.payer,
+        77
+      );
+      await program.methods
+        .cancelOffer()
+        .accounts(closeAccounts(buyer.publicKey))
+        .signers([buyer])
+        .rpc({ commitment: 'confirmed' });
+
+      assert.strictEqual(await balance(buyer.publicKey), buyerBefore + 77);
+      assert.isNull(await connection.getAccountInfo(offer, 'confirmed'));
+      assert.isNull(await connection.getAccountInfo(offerEscrow, 'confirmed'));
+    });
+
+    it('Lets anyone return an offer once it has expired', async () => {
+      const buyerBefore = await balance(buyer.publicKey);
+      await makeOffer(3);
+
+      await expectError(
+        program.methods
+          .expireOffer()
+          .accounts(closeAccounts(wallet.publicKey))
+          .rpc(),
+        'OfferNotExpired'
+      );
+      const { expiresAt } = await program.account.offer.fetch(
+        offer,
+        'confirmed'
+      );
+      await sleep(Math.max(0, expiresAt.toNumber() - Date.now() / 1000) + 2);
+
+      await program.methods
+        .expireOffer()
+        .accounts(closeAccounts(wallet.publicKey))
+        .rpc({ commitment: 'confirmed' });
+
+      assert.strictEqual(await balance(buyer.publicKey), buyerBefore);
+      assert.isNull(await connection.getAccountInfo(offer, 'confirmed'));
+    });
+
+    it('Rejects accepting an offer while the parcel is listed', async () => {
+      const listing = pda(Buffer.from('listing'), parcel.assetId.toBuffer());
+      const leaf = tree.leaf(parcel);
+      await program.methods
+        .listParcel(
+          leaf.root,
+          leaf.nonce,
+          leaf.index,
+          parcel.metadata,
+          new anchor.BN(2 * amount)
+        )
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          collectionConfig: tree.collectionConfig,
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            parcel.assetId.toBuffer()
+          ),
+          auction: pda(Buffer.from('auction'), parcel.assetId.toBuffer()),
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          treeAuthority: tree.treeConfig,
+          previousLeafDelegate: parcel.delegate,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(parcel, listing);
+      await makeOffer(600);
+
+      await expectError(accept(), 'ParcelOnSale');
+
+      await program.methods
+        .cancelListing(tree.root())
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+        })
+        .remainingAccounts(tree.proof(parcel))
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(parcel, seller.publicKey);
+    });
+
+    it('Transfers the parcel to the buyer when the owner accepts', async () => {
+      const sellerBefore = await balance(seller.publicKey);
+      // stray tokens in the escrow go to the seller with the proceeds
+      await mintTo(
+        connection,
+        wallet.payer,
+        mint,
+        offerEscrow,
+        wallet.payer,
+        77
+      );
+
+      await accept();
+      tree.setOwner(parcel, buyer.publicKey);
+
+      const fee = (amount * saleBps) / 10_000;
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        amount - fee + 77
+      );
+      assert.isNull(await connection.getAccountInfo(offer, 'confirmed'));
+      assert.isNull(await connection.getAccountInfo(offerEscrow, 'confirmed'));
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('leases', () => {
+    const ratePerSecond = 10_000;
+    // 10% of the rent goes to the treasury
+    const leaseBps = 1_000;
+    let parcel: Parcel;
+    let lease: PublicKey;
+    let leaseEscrow: PublicKey;
+    let parcelState: PublicKey;
+
+    const transfer = () => {
+      const leaf = tree.leaf(parcel);
+      return program.methods
+        .transferNft(
+          leaf.root,
+          leaf.dataHash,
+          leaf.creatorHash,
+          leaf.nonce,
+          leaf.index,
+          false
+        )
+        .accounts({
+          treeAuthority: tree.treeConfig,
+          authority: seller.publicKey,
+          leafOwner: seller.publicKey,
+          leafDelegate: parcel.delegate,
+          newLeafOwner: buyer.publicKey,
+          parcelState,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    };
+    const settleAccounts = () => ({
+      lease,
+      leaseEscrow,
+      ownerTokenAccount: tokenAccount(seller.publicKey),
+      config: pda(Buffer.from('config')),
+      treasuryVault: pda(Buffer.from('treasury_vault'), mint.toBuffer()),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+    const close = () =>
+      program.methods
+        .closeLease()
+        .accounts({
+          ...settleAccounts(),
+          signer: wallet.publicKey,
+          parcelState,
+          owner: seller.publicKey,
+          lessee: buyer.publicKey,
+          lesseeTokenAccount: tokenAccount(buyer.publicKey),
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    before(async () => {
+      await setConfig(program, {
+        mintBps: 0,
+        lockBps: 0,
+        saleBps: 0,
+        leaseBps,
+      });
+      parcel = await tree.mint(seller.publicKey);
+      lease = pda(Buffer.from('lease'), parcel.assetId.toBuffer());
+      leaseEscrow = pda(Buffer.from('lease_escrow'), lease.toBuffer());
+      parcelState = pda(Buffer.from('parcel_state'), parcel.assetId.toBuffer());
+    });
+
+    it('Escrows the whole term\'s rent when a lease is created', async () => {
+      const startTime = Math.floor(Date.now() / 1000) + 2;
+      const endTime = startTime + 6;
+      const deposit = ratePerSecond * (endTime - startTime);
+      const leaf = tree.leaf(parcel);
+      const lesseeBefore = await balance(buyer.publicKey);
+
+      await program.methods
+        .createLease(
+          leaf.root,
+          leaf.dataHash,
+          leaf.creatorHash,
+          leaf.nonce,
+          leaf.index,
+          {
+            startTime: new anchor.BN(startTime),
+            endTime: new anchor.BN(endTime),
+            ratePerSecond: new anchor.BN(ratePerSecond),
+          }
+        )
+        .accounts({
+          owner: seller.publicKey,
+          lessee: buyer.publicKey,
+          leafDelegate: parcel.delegate,
+          lease,
+          leaseEscrow,
+          parcelState,
+          whitelist: pda(Buffer.from('token_whitelist')),
+          paymentMint: mint,
+          lesseeTokenAccount: tokenAccount(buyer.publicKey),
+          merkleTree: tree.merkleTree,
+          compressionProgram: tree.treeAccounts().compressionProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller, buyer])
+        .rpc({ commitment: 'confirmed' });
+
+      const created = await program.account.lease.fetch(lease, 'confirmed');
+      assert.ok(created.owner.equals(seller.publicKey));
+      assert.ok(created.lessee.equals(buyer.publicKey));
+      assert.strictEqual(created.deposit.toNumber(), deposit);
+      assert.strictEqual(
+        lesseeBefore - (await balance(buyer.publicKey)),
+        deposit
+      );
+      const state = await program.account.parcelState.fetch(
+        parcelState,
+        'confirmed'
+      );
+      assert.strictEqual(state.leaseEnd.toNumber(), endTime);
+    });
+
+    it('Blocks transfers and burns while the parcel is leased', async () => {
+      await expectError(transfer(), 'LeaseActive');
+      await expectError(
+        burnParcel(program, tree, parcel, seller, []),
+        'LeaseActive'
+      );
+      await expectError(close(), 'LeaseNotEnded');
+    });
+
+    it('Streams the rent accrued so far to the owner', async () => {
+      const { startTime } = await program.account.lease.fetch(
+        lease,
+        'confirmed'
+      );
+      await sleep(Math.max(0, startTime.toNumber() - Date.now() / 1000) + 2);
+      const ownerBefore = await balance(seller.publicKey);
+
+      await program.methods
+        .claimLeaseRent()
+        .accounts({ ...settleAccounts(), owner: seller.publicKey })
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+
+      const { claimed, deposit } = await program.account.lease.fetch(
+        lease,
+        'confirmed'
+      );
+      assert.isAbove(claimed.toNumber(), 0);
+      assert.isBelow(claimed.toNumber(), deposit.toNumber());
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - ownerBefore,
+        claimed.toNumber() - (claimed.toNumber() * leaseBps) / 10_000
+      );
+    });
+
+    it('Refuses to burn the parcel until an expired lease is closed', async () => {
+      const { endTime } = await program.account.lease.fetch(lease, 'confirmed');
+      await sleep(Math.max(0, endTime.toNumber() - Date.now() / 1000) + 2);
+
+      // burning would close the parcel state close_lease settles against
+      await expectError(
+        burnParcel(program, tree, parcel, seller, []),
+        'LeaseNotClosed'
+      );
+      assert.isNotNull(
+        await connection.getAccountInfo(parcelState, 'confirmed')
+      );
+    });
+
+    it('Pays out the rest on close and frees the parcel', async () => {
+      const { endTime, deposit, claimed } = await program.account.lease.fetch(
+        lease,
+        'confirmed'
+      );
+      await sleep(Math.max(0, endTime.toNumber() - Date.now() / 1000) + 2);
+      const rest = deposit.toNumber() - claimed.toNumber();
+      const ownerBefore = await balance(seller.publicKey);
+      const lesseeBefore = await balance(buyer.publicKey);
+
+      await close();
+
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - ownerBefore,
+        rest - (rest * leaseBps) / 10_000
+      );
+      // the full term ran, so nothing goes back to the lessee
+      assert.strictEqual(await balance(buyer.publicKey), lesseeBefore);
+      assert.isNull(await connection.getAccountInfo(lease, 'confirmed'));
+
+      await transfer();
+      tree.setOwner(parcel, buyer.publicKey);
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('swaps', () => {
+    const topUp = 1_000_000;
+    let offered: Parcel;
+    let requested: Parcel;
+    let swapOffer: PublicKey;
+    let swapEscrow: PublicKey;
+
+    const makeSwap = () => {
+      const leaf = tree.leaf(offered);
+      return program.methods
+        .makeSwap(
+          leaf.root,
+          leaf.dataHash,
+          leaf.creatorHash,
+          leaf.nonce,
+          leaf.index,
+          {
+            taker: buyer.publicKey,
+            requestedAsset: requested.assetId,
+            topUp: {
+              paymentMint: mint,
+              amount: new anchor.BN(topUp),
+              paidByMaker: true,
+            },
+            expiresAt: new anchor.BN(Math.floor(Date.now() / 1000) + 600),
+          }
+        )
+        .accounts({
+          maker: seller.publicKey,
+          swapOffer,
+          swapEscrow,
+          makerTokenAccount: tokenAccount(seller.publicKey),
+          paymentMint: mint,
+          whitelist: pda(Buffer.from('token_whitelist')),
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            offered.assetId.toBuffer()
+          ),
+          treeAuthority: tree.treeConfig,
+          previousLeafDelegate: offered.delegate,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(leaf.proof)
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    };
+    const accept = (taker: Keypair) => {
+      const makerProof = tree.proof(offered);
+      const takerLeaf = tree.leaf(requested);
+      return program.methods
+        .acceptSwap(
+          tree.root(),
+          {
+            root: takerLeaf.root,
+            dataHash: takerLeaf.dataHash,
+            creatorHash: takerLeaf.creatorHash,
+            nonce: takerLeaf.nonce,
+            index: takerLeaf.index,
+          },
+          makerProof.length
+        )
+        .accounts({
+          taker: taker.publicKey,
+          swapOffer,
+          maker: seller.publicKey,
+          parcelState: pda(
+            Buffer.from('parcel_state'),
+            offered.assetId.toBuffer()
+          ),
+          treeAuthority: tree.treeConfig,
+          takerLeafDelegate: requested.delegate,
+          takerParcelState: pda(
+            Buffer.from('parcel_state'),
+            requested.assetId.toBuffer()
+          ),
+          takerTreeAuthority: tree.treeConfig,
+          takerMerkleTree: tree.merkleTree,
+          swapEscrow,
+          takerTokenAccount: tokenAccount(taker.publicKey),
+          makerTokenAccount: tokenAccount(seller.publicKey),
+          whitelist: pda(Buffer.from('token_whitelist')),
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(makerProof.concat(takerLeaf.proof))
+        .signers([taker])
+        .rpc({ commitment: 'confirmed' });
+    };
+
+    before(async () => {
+      offered = await tree.mint(seller.publicKey);
+      requested = await tree.mint(buyer.publicKey);
+      swapOffer = pda(Buffer.from('swap_offer'), offered.assetId.toBuffer());
+      swapEscrow = pda(Buffer.from('swap_escrow'), swapOffer.toBuffer());
+    });
+
+    it('Escrows the maker\'s top-up and delegates the leaf to the swap', async () => {
+      const makerBefore = await balance(seller.publicKey);
+      await makeSwap();
+      tree.setDelegate(offered, swapOffer);
+
+      const made = await program.account.swapOffer.fetch(
+        swapOffer,
+        'confirmed'
+      );
+      assert.ok(made.taker.equals(buyer.publicKey));
+      assert.ok(made.requestedAsset.equals(requested.assetId));
+      assert.strictEqual(
+        makerBefore - (await balance(seller.publicKey)),
+        topUp
+      );
+      assert.strictEqual(
+        Number((await getAccount(connection, swapEscrow, 'confirmed')).amount),
+        topUp
+      );
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Returns the top-up and the delegate when the maker cancels', async () => {
+      const makerBefore = await balance(seller.publicKey);
+
+      await program.methods
+        .cancelSwap(tree.root())
+        .accounts({
+          maker: seller.publicKey,
+          swapOffer,
+          swapEscrow,
+          makerTokenAccount: tokenAccount(seller.publicKey),
+          treeAuthority: tree.treeConfig,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(tree.proof(offered))
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+      tree.setDelegate(offered, seller.publicKey);
+
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - makerBefore,
+        topUp
+      );
+      assert.isNull(await connection.getAccountInfo(swapOffer, 'confirmed'));
+      assert.isNull(await connection.getAccountInfo(swapEscrow, 'confirmed'));
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+
+    it('Only lets the named taker accept', async () => {
+      await makeSwap();
+      tree.setDelegate(offered, swapOffer);
+
+      await expectError(accept(wallet.payer), 'ConstraintHasOne');
+    });
+
+    it('Settles both parcels and the top-up in one transaction', async () => {
+      const takerBefore = await balance(buyer.publicKey);
+
+      await accept(buyer);
+      tree.setOwner(offered, buyer.publicKey);
+      tree.setOwner(requested, seller.publicKey);
+
+      assert.strictEqual(
+        (await balance(buyer.publicKey)) - takerBefore,
+        topUp
+      );
+      assert.isNull(await connection.getAccountInfo(swapOffer, 'confirmed'));
+      assert.isNull(await connection.getAccountInfo(swapEscrow, 'confirmed'));
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('burn ledger', () => {
+    // a parcel owner with no account for the payment mint yet
+    const holder = Keypair.generate();
+    let parcel: Parcel;
+
+    before(async () => {
+      await setConfig(program);
+      await airdrop(provider, holder.publicKey);
+      parcel = await tree.mint(holder.publicKey);
+    });
+
+    it('Records every deposit against the parcel in its own entry', async () => {
+      await lockFund(program, tree, parcel, seller, mint, {
+        sponsorship: { owner: holder.publicKey, refundRule: { depositor: {} } },
+      });
+      await lockFund(program, tree, parcel, buyer, mint, {
+        sponsorship: { owner: holder.publicKey, refundRule: { owner: {} } },
+      });
+
+      for (const [depositor, beneficiary] of [
+        [seller.publicKey, seller.publicKey],
+        [buyer.publicKey, holder.publicKey],
+      ]) {
+        const entry = await program.account.stakeInfo.fetch(
+          stakeAccounts(program, parcel, depositor, mint).stakeInfo,
+          'confirmed'
+        );
+        assert.ok(entry.depositor.equals(depositor));
+        assert.ok(entry.beneficiary.equals(beneficiary));
+        assert.ok(entry.owner.equals(holder.publicKey));
+        assert.strictEqual(entry.amount.toNumber(), assetPrice);
+        assert.strictEqual(entry.assetPrice.toNumber(), assetPrice);
+      }
+      const state = await program.account.parcelState.fetch(
+        pda(Buffer.from('parcel_state'), parcel.assetId.toBuffer()),
+        'confirmed'
+      );
+      assert.strictEqual(state.stakers, 2);
+      assert.strictEqual(state.lockedAmount.toNumber(), 2 * assetPrice);
+    });
+
+    it('Refunds each entry on burn and sweeps stray tokens to the owner', async () => {
+      const { cnftStakeVault } = stakeAccounts(
+        program,
+        parcel,
+        seller.publicKey,
+        mint
+      );
+      // tokens sent to the vault outside lock_fund belong to no entry
+      await mintTo(
+        connection,
+        wallet.payer,
+        mint,
+        cnftStakeVault,
+        wallet.payer,
+        123
+      );
+      assert.isNull(
+        await connection.getAccountInfo(tokenAccount(holder.publicKey))
+      );
+      const sellerBefore = await balance(seller.publicKey);
+      const buyerBefore = await balance(buyer.publicKey);
+
+      await burnParcel(program, tree, parcel, holder, [
+        {
+          depositor: seller.publicKey,
+          beneficiary: seller.publicKey,
+          mint,
+        },
+        { depositor: buyer.publicKey, beneficiary: holder.publicKey, mint },
+      ]);
+
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        assetPrice
+      );
+      assert.strictEqual(await balance(buyer.publicKey), buyerBefore);
+      // created by the burn, holding the owner-ruled refund and the sweep
+      assert.strictEqual(await balance(holder.publicKey), assetPrice + 123);
+      for (const depositor of [seller.publicKey, buyer.publicKey]) {
+        assert.isNull(
+          await connection.getAccountInfo(
+            stakeAccounts(program, parcel, depositor, mint).stakeInfo,
+            'confirmed'
+          )
+        );
+      }
+      assert.isNull(
+        await connection.getAccountInfo(cnftStakeVault, 'confirmed')
+      );
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('unlock cooldown', () => {
+    const unlockCooldown = 3;
+    let parcel: Parcel;
+    let stake: ReturnType<typeof stakeAccounts>;
+
+    before(async () => {
+      await setConfig(
+        program,
+        { mintBps: 0, lockBps: 0, saleBps: 0, leaseBps: 0 },
+        unlockCooldown
+      );
+      parcel = await tree.mint(seller.publicKey);
+      stake = await lockFund(program, tree, parcel, seller, mint);
+    });
+
+    it('Refuses a withdrawal that was never requested', async () => {
+      await expectError(
+        withdrawUnlocked(program, parcel, seller, seller.publicKey, mint),
+        'UnlockNotRequested'
+      );
+    });
+
+    it('Holds the deposit until the cooldown has passed', async () => {
+      await requestUnlock(program, parcel, seller, seller.publicKey, mint);
+
+      const { unlockAvailableAt } = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      assert.isAbove(unlockAvailableAt.toNumber(), 0);
+      await expectError(
+        withdrawUnlocked(program, parcel, seller, seller.publicKey, mint),
+        'UnlockCooldownActive'
+      );
+    });
+
+    it('Returns the deposit and leaves the parcel alive', async () => {
+      const { unlockAvailableAt } = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      await sleep(
+        Math.max(0, unlockAvailableAt.toNumber() - Date.now() / 1000) + 2
+      );
+      const sellerBefore = await balance(seller.publicKey);
+      // the last depositor out takes tokens sent to the vault outside
+      // lock_fund, so the vault can still be closed
+      await mintTo(
+        connection,
+        wallet.payer,
+        mint,
+        stake.cnftStakeVault,
+        wallet.payer,
+        123
+      );
+
+      await withdrawUnlocked(program, parcel, seller, seller.publicKey, mint);
+
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        assetPrice + 123
+      );
+      assert.isNull(
+        await connection.getAccountInfo(stake.stakeInfo, 'confirmed')
+      );
+      assert.isNull(
+        await connection.getAccountInfo(stake.cnftStakeVault, 'confirmed')
+      );
+      const state = await program.account.parcelState.fetch(
+        stake.parcelState,
+        'confirmed'
+      );
+      assert.strictEqual(state.stakers, 0);
+      assert.strictEqual(state.lockedAmount.toNumber(), 0);
+      // the leaf was never touched
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('proof-checked burns', () => {
+    let parcel: Parcel;
+    let stake: ReturnType<typeof stakeAccounts>;
+    const ledger = () => [
+      { depositor: seller.publicKey, beneficiary: seller.publicKey, mint },
+    ];
+    const vaultAmount = async () =>
+      Number(
+        (await getAccount(connection, stake.cnftStakeVault, 'confirmed'))
+          .amount
+      );
+
+    before(async () => {
+      await setConfig(program);
+      parcel = await tree.mint(seller.publicKey);
+      stake = await lockFund(program, tree, parcel, seller, mint);
+    });
+
+    it('Rejects a burn signed by someone other than the leaf owner', async () => {
+      await expectError(
+        burnParcel(program, tree, parcel, buyer, ledger()),
+        'ConcurrentMerkleTreeError'
+      );
+      assert.strictEqual(await vaultAmount(), assetPrice);
+    });
+
+    it('Rejects a burn with metadata the leaf was not minted with', async () => {
+      const forged = {
+        ...parcel,
+        metadata: { ...parcel.metadata, uri: 'https://example.com/forged' },
+      };
+      await expectError(
+        burnParcel(program, tree, forged, seller, ledger()),
+        'ConcurrentMerkleTreeError'
+      );
+      assert.strictEqual(await vaultAmount(), assetPrice);
+    });
+
+    it('Releases the deposit once the owner\'s leaf is proven', async () => {
+      const sellerBefore = await balance(seller.publicKey);
+
+      await burnParcel(program, tree, parcel, seller, ledger());
+
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        assetPrice
+      );
+      assert.isNull(
+        await connection.getAccountInfo(stake.cnftStakeVault, 'confirmed')
+      );
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('top-ups and partial unlocks', () => {
+    const unlockCooldown = 2;
+    let parcel: Parcel;
+    let stake: ReturnType<typeof stakeAccounts>;
+
+    const topUp = (amount: number) =>
+      program.methods
+        .topUpLock(new anchor.BN(amount))
+        .accounts({
+          depositor: seller.publicKey,
+          stakeInfo: stake.stakeInfo,
+          assetInfo: assetInfoFor(program, parcel.assetId),
+          parcelState: stake.parcelState,
+          depositorTokenAccount: tokenAccount(seller.publicKey),
+          cnftStakeVault: stake.cnftStakeVault,
+          pooledCustody: null,
+          pooledVault: null,
+          rewardPool: stake.rewardPool,
+          config: stake.config,
+          treasuryVault: stake.treasuryVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    const partialUnlock = (amount: number) =>
+      program.methods
+        .partialUnlock(new anchor.BN(amount))
+        .accounts({
+          beneficiary: seller.publicKey,
+          stakeInfo: stake.stakeInfo,
+          assetInfo: assetInfoFor(program, parcel.assetId),
+          parcelState: stake.parcelState,
+          cnftStakeVault: stake.cnftStakeVault,
+          pooledCustody: null,
+          pooledVault: null,
+          vaultAuthority: stake.vaultAuthority,
+          rewardPool: stake.rewardPool,
+          beneficiaryTokenAccount: tokenAccount(seller.publicKey),
+          config: stake.config,
+          treasuryVault: stake.treasuryVault,
+          penaltyRecord: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+    const deposited = async () =>
+      (
+        await program.account.stakeInfo.fetch(stake.stakeInfo, 'confirmed')
+      ).amount.toNumber();
+
+    before(async () => {
+      await setConfig(
+        program,
+        { mintBps: 0, lockBps: 0, saleBps: 0, leaseBps: 0 },
+        unlockCooldown
+      );
+      parcel = await tree.mint(seller.publicKey);
+      stake = await lockFund(program, tree, parcel, seller, mint);
+    });
+
+    it('Tops up an open deposit without restarting its lock', async () => {
+      const { lockedAt } = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+
+      await topUp(assetPrice / 2);
+
+      const entry = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      assert.strictEqual(entry.amount.toNumber(), (3 * assetPrice) / 2);
+      assert.strictEqual(entry.lockedAt.toNumber(), lockedAt.toNumber());
+      const state = await program.account.parcelState.fetch(
+        stake.parcelState,
+        'confirmed'
+      );
+      assert.strictEqual(state.lockedAmount.toNumber(), (3 * assetPrice) / 2);
+      assert.strictEqual(state.stakers, 1);
+    });
+
+    it('Holds a partial unlock to the cooldown', async () => {
+      await expectError(partialUnlock(assetPrice / 4), 'UnlockNotRequested');
+
+      await requestUnlock(program, parcel, seller, seller.publicKey, mint);
+
+      await expectError(partialUnlock(assetPrice / 4), 'UnlockCooldownActive');
+    });
+
+    it('Keeps the deposit at or above the asset price', async () => {
+      const { unlockAvailableAt } = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      await sleep(
+        Math.max(0, unlockAvailableAt.toNumber() - Date.now() / 1000) + 2
+      );
+      const sellerBefore = await balance(seller.publicKey);
+
+      await expectError(
+        partialUnlock((3 * assetPrice) / 4),
+        'BelowMinimumDeposit'
+      );
+      await partialUnlock(assetPrice / 2);
+
+      assert.strictEqual(await deposited(), assetPrice);
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        assetPrice / 2
+      );
+      // each partial unlock uses up its request
+      await expectError(partialUnlock(1), 'UnlockNotRequested');
+    });
+  });
+
+  describe('penalties and slashing', () => {
+    // exits within the first hour forfeit 10% to the treasury
+    const penaltyBps = 1_000;
+    const arbitrator = Keypair.generate();
+    const treasuryVault = () =>
+      pda(Buffer.from('treasury_vault'), mint.toBuffer());
+    const treasuryAmount = async () =>
+      Number(
+        (await getAccount(connection, treasuryVault(), 'confirmed')).amount
+      );
+    // the record the next penalty will be written to
+    const nextPenaltyRecord = async () => {
+      const { penalties } = await program.account.config.fetch(
+        pda(Buffer.from('config')),
+        'confirmed'
+      );
+      return pda(
+        Buffer.from('penalty_record'),
+        penalties.toArrayLike(Buffer, 'le', 8)
+      );
+    };
+    let early: Parcel;
+    let disputed: Parcel;
+    let stake: ReturnType<typeof stakeAccounts>;
+
+    const slash = async (signer: Keypair, amount: number, reasonCode = 7) => {
+      const penaltyRecord = await nextPenaltyRecord();
+      await program.methods
+        .slashDeposit(new anchor.BN(amount), reasonCode)
+        .accounts({
+          arbitrator: signer.publicKey,
+          config: stake.config,
+          depositor: buyer.publicKey,
+          stakeInfo: stake.stakeInfo,
+          parcelState: stake.parcelState,
+          cnftStakeVault: stake.cnftStakeVault,
+          pooledCustody: null,
+          pooledVault: null,
+          vaultAuthority: stake.vaultAuthority,
+          rewardPool: stake.rewardPool,
+          treasuryVault: treasuryVault(),
+          penaltyRecord,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc({ commitment: 'confirmed' });
+      return penaltyRecord;
+    };
+
+    before(async () => {
+      await setConfig(program);
+      await setPenaltyPolicy(program, arbitrator.publicKey, 3_600, penaltyBps);
+      await airdrop(provider, arbitrator.publicKey);
+      early = await tree.mint(seller.publicKey);
+      disputed = await tree.mint(buyer.publicKey);
+      await lockFund(program, tree, early, seller, mint);
+      stake = await lockFund(program, tree, disputed, buyer, mint);
+    });
+
+    after(async () => {
+      await setPenaltyPolicy(program, wallet.publicKey);
+    });
+
+    it('Withholds the penalty from an early exit and records it', async () => {
+      await requestUnlock(program, early, seller, seller.publicKey, mint);
+      await expectError(
+        withdrawUnlocked(program, early, seller, seller.publicKey, mint),
+        'PenaltyRecordMismatch'
+      );
+      const penaltyRecord = await nextPenaltyRecord();
+      const sellerBefore = await balance(seller.publicKey);
+      const treasuryBefore = await treasuryAmount();
+
+      await withdrawUnlocked(program, early, seller, seller.publicKey, mint, {
+        penaltyRecord,
+      });
+
+      const penalty = (assetPrice * penaltyBps) / 10_000;
+      assert.strictEqual(
+        (await balance(seller.publicKey)) - sellerBefore,
+        assetPrice - penalty
+      );
+      assert.strictEqual((await treasuryAmount()) - treasuryBefore, penalty);
+      const record = await program.account.penaltyRecord.fetch(
+        penaltyRecord,
+        'confirmed'
+      );
+      assert.deepEqual(record.kind, { earlyUnlock: {} });
+      assert.ok(record.assetId.equals(early.assetId));
+      assert.ok(record.depositor.equals(seller.publicKey));
+      assert.ok(record.authority.equals(seller.publicKey));
+      assert.strictEqual(record.amount.toNumber(), penalty);
+      assert.strictEqual(record.reasonCode, 0);
+    });
+
+    it('Only lets the arbitrator slash a deposit', async () => {
+      await expectError(
+        slash(wallet.payer, assetPrice / 4),
+        'ConstraintHasOne'
+      );
+    });
+
+    it('Slashes part of a deposit into the treasury on a ruling', async () => {
+      const treasuryBefore = await treasuryAmount();
+
+      const penaltyRecord = await slash(arbitrator, assetPrice / 4);
+
+      assert.strictEqual(
+        (await treasuryAmount()) - treasuryBefore,
+        assetPrice / 4
+      );
+      const entry = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      assert.strictEqual(entry.amount.toNumber(), (3 * assetPrice) / 4);
+      assert.isTrue(entry.open);
+      const record = await program.account.penaltyRecord.fetch(
+        penaltyRecord,
+        'confirmed'
+      );
+      assert.deepEqual(record.kind, { slash: {} });
+      assert.ok(record.depositor.equals(buyer.publicKey));
+      assert.ok(record.authority.equals(arbitrator.publicKey));
+      assert.strictEqual(record.amount.toNumber(), assetPrice / 4);
+      assert.strictEqual(record.reasonCode, 7);
+    });
+
+    it('Ends the entry when a slash takes the rest of the deposit', async () => {
+      await slash(arbitrator, (3 * assetPrice) / 4);
+
+      assert.isNull(
+        await connection.getAccountInfo(stake.stakeInfo, 'confirmed')
+      );
+      const state = await program.account.parcelState.fetch(
+        stake.parcelState,
+        'confirmed'
+      );
+      assert.strictEqual(state.stakers, 0);
+      assert.strictEqual(state.lockedAmount.toNumber(), 0);
+
+      // the depositor can lock against the parcel afresh
+      await lockFund(program, tree, disputed, buyer, mint);
+      const reopened = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      assert.strictEqual(reopened.amount.toNumber(), assetPrice);
+    });
+  });
+
+  describe('burning unpinned parcels', () => {
+    it('Burns a parcel that was never locked or leased', async () => {
+      const parcel = await tree.mint(seller.publicKey);
+      const before = await program.account.collectionConfig.fetch(
+        tree.collectionConfig,
+        'confirmed'
+      );
+      assert.isNull(
+        await connection.getAccountInfo(
+          pda(Buffer.from('parcel_state'), parcel.assetId.toBuffer()),
+          'confirmed'
+        )
+      );
+
+      await burnParcel(program, tree, parcel, seller, []);
+
+      const after = await program.account.collectionConfig.fetch(
+        tree.collectionConfig,
+        'confirmed'
+      );
+      assert.strictEqual(after.live.toNumber(), before.live.toNumber() - 1);
+      assert.strictEqual(
+        after.burned.toNumber(),
+        before.burned.toNumber() + 1
+      );
+      assert.deepEqual(await tree.onChainRoot(), tree.root());
+    });
+  });
+
+  describe('asset valuations', () => {
+    let parcel: Parcel;
+
+    before(async () => {
+      parcel = await tree.mint(seller.publicKey);
+    });
+
+    it('Only lets the admin value an asset', async () => {
+      await expectError(
+        program.methods
+          .valueAsset(parcel.assetId, {
+            name: 'KONNA',
+            metadataUrl: '',
+            price: new anchor.BN(1),
+            reputation: { high: {} },
+          })
+          .accounts({
+            admin: seller.publicKey,
+            config: pda(Buffer.from('config')),
+            assetInfo: assetInfoFor(program, parcel.assetId),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([seller])
+          .rpc({ commitment: 'confirmed' }),
+        'ConstraintHasOne'
+      );
+    });
+
+    it('Revalues an asset in place', async () => {
+      const assetInfo = assetInfoFor(program, parcel.assetId);
+      const valued = await program.account.asset.fetch(assetInfo, 'confirmed');
+      assert.ok(valued.id.equals(parcel.assetId));
+      assert.strictEqual(valued.price.toNumber(), assetPrice);
+
+      await valueAsset(program, parcel.assetId, 2 * assetPrice);
+
+      const revalued = await program.account.asset.fetch(
+        assetInfo,
+        'confirmed'
+      );
+      assert.strictEqual(revalued.price.toNumber(), 2 * assetPrice);
+      assert.ok(revalued.authority.equals(wallet.publicKey));
+    });
+  });
+
+  describe('rewards on transfer', () => {
+    const emissionRate = 1_000;
+    let stakeMint: PublicKey;
+    let rewardMint: PublicKey;
+    let rewardPool: PublicKey;
+    let rewardVault: PublicKey;
+    let parcel: Parcel;
+    let stake: ReturnType<typeof stakeAccounts>;
+
+    const rewardAccount = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(rewardMint, owner);
+    const rewards = async (owner: PublicKey) =>
+      Number(
+        (await getAccount(connection, rewardAccount(owner), 'confirmed')).amount
+      );
+    const lastUpdate = async () =>
+      (
+        await program.account.rewardPool.fetch(rewardPool, 'confirmed')
+      ).lastUpdate.toNumber();
+    const claim = (beneficiary: Keypair) =>
+      program.methods
+        .claimRewards()
+        .accounts({
+          beneficiary: beneficiary.publicKey,
+          depositor: seller.publicKey,
+          stakeInfo: stake.stakeInfo,
+          rewardPool,
+          rewardVault,
+          beneficiaryRewardAccount: rewardAccount(beneficiary.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: 'confirmed' });
+
+    before(async () => {
+      await setConfig(program);
+      ({ mint: stakeMint } = await createPaymentMint(
+        program,
+        [seller],
+        10 * assetPrice
+      ));
+      ({ mint: rewardMint } = await createPaymentMint(
+        program,
+        [wallet.payer, seller, buyer],
+        100_000_000
+      ));
+      rewardPool = pda(Buffer.from('reward_pool'), stakeMint.toBuffer());
+      rewardVault = pda(Buffer.from('reward_vault'), rewardPool.toBuffer());
+      await program.methods
+        .initRewardPool(new anchor.BN(emissionRate))
+        .accounts({
+          admin: wallet.publicKey,
+          config: pda(Buffer.from('config')),
+          rewardPool,
+          rewardVault,
+          whitelist: pda(Buffer.from('token_whitelist')),
+          stakeMint,
+          rewardMint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: 'confirmed' });
+      await program.methods
+        .fundRewardPool(new anchor.BN(100_000_000))
+        .accounts({
+          admin: wallet.publicKey,
+          config: pda(Buffer.from('config')),
+          rewardPool,
+          rewardVault,
+          adminTokenAccount: rewardAccount(wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      parcel = await tree.mint(seller.publicKey);
+      stake = await lockFund(program, tree, parcel, seller, stakeMint);
+    });
+
+    it('Pays the seller what the deposit earned before it is carried over', async () => {
+      const lockedAt = await lastUpdate();
+      await sleep(3);
+      const sellerBefore = await rewards(seller.publicKey);
+      const writable = (pubkey: PublicKey) => ({
+        pubkey,
+        isSigner: false,
+        isWritable: true,
+      });
+      const leaf = tree.leaf(parcel);
+
+      await program.methods
+        .transferNft(
+          leaf.root,
+          leaf.dataHash,
+          leaf.creatorHash,
+          leaf.nonce,
+          leaf.index,
+          true
+        )
+        .accounts({
+          treeAuthority: tree.treeConfig,
+          authority: seller.publicKey,
+          leafOwner: seller.publicKey,
+          leafDelegate: parcel.delegate,
+          newLeafOwner: buyer.publicKey,
+          parcelState: stake.parcelState,
+          ...tree.treeAccounts(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          leaf.proof.concat([
+            writable(stake.stakeInfo),
+            writable(rewardPool),
+            writable(rewardVault),
+            writable(rewardAccount(seller.publicKey)),
+          ])
+        )
+        .signers([seller])
+        .rpc({ commitment: 'confirmed' });
+      tree.setOwner(parcel, buyer.publicKey);
+
+      // the deposit was the pool's only stake, so it earned every emission
+      assert.strictEqual(
+        (await rewards(seller.publicKey)) - sellerBefore,
+        ((await lastUpdate()) - lockedAt) * emissionRate
+      );
+      const entry = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      assert.ok(entry.beneficiary.equals(buyer.publicKey));
+      assert.strictEqual(entry.pendingRewards.toNumber(), 0);
+    });
+
+    it('Leaves the new owner only what accrued after the transfer', async () => {
+      const transferredAt = await lastUpdate();
+      await sleep(2);
+      const buyerBefore = await rewards(buyer.publicKey);
+
+      await expectError(claim(seller), 'ConstraintHasOne');
+      await claim(buyer);
+
+      assert.strictEqual(
+        (await rewards(buyer.publicKey)) - buyerBefore,
+        ((await lastUpdate()) - transferredAt) * emissionRate
+      );
+    });
+  });
+});
@@ -0,0 +1,426 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import { Keypair, PublicKey, SystemProgram } from '@solana/web3.js';
+import fs from 'fs';
+import { assert } from 'chai';
+import { MintNftSkytrade } from '../target/types/mint_nft_skytrade';
+import {
+  Parcel,
+  ParcelTree,
+  Sponsorship,
+  airdrop,
+  burnParcel,
+  createPaymentMint,
+  expectError,
+  loadKeypair,
+  lockFund,
+  programPda,
+  requestUnlock,
+  setConfig,
+  stakeAccounts,
+  withdrawUnlocked,
+} from '../utils/localnet';
+
+// Runs against a local validator (`anchor test --provider.cluster localnet`),
+// which preloads the Asset valuations of the vault tree's leaves.
+describe('vault custody', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+  const program = anchor.workspace.MintNftSkytrade as Program<MintNftSkytrade>;
+
+  const tree = new ParcelTree(
+    program,
+    loadKeypair('tests/fixtures/vault-tree.json')
+  );
+  const assetPrice = ParcelTree.assetPrice;
+
+  let mint: PublicKey;
+  let depositorAta: PublicKey;
+  let parcel: Parcel;
+
+  before(async () => {
+    await tree.init();
+    ({
+      mint,
+      accounts: [depositorAta],
+    } = await createPaymentMint(program, [wallet.payer], 10 * assetPrice));
+    // no fees and no cooldown, so the whole deposit can come straight back
+    await setConfig(program);
+    parcel = await tree.mint(wallet.publicKey);
+  });
+
+  it('Locks funds into a vault held by the vault authority', async () => {
+    const { cnftStakeVault, stakeInfo, vaultAuthority } = await lockFund(
+      program,
+      tree,
+      parcel,
+      wallet.payer,
+      mint
+    );
+
+    const vault = await getAccount(connection, cnftStakeVault, 'confirmed');
+    assert.strictEqual(Number(vault.amount), assetPrice);
+    assert.ok(vault.owner.equals(vaultAuthority));
+
+    const ledger = await program.account.stakeInfo.fetch(
+      stakeInfo,
+      'confirmed'
+    );
+    assert.ok(ledger.depositor.equals(wallet.publicKey));
+    assert.strictEqual(ledger.amount.toNumber(), assetPrice);
+  });
+
+  it('Withdraws the unlocked deposit and closes the vault', async () => {
+    const stake = stakeAccounts(program, parcel, wallet.publicKey, mint);
+    const before = await getAccount(connection, depositorAta, 'confirmed');
+
+    await requestUnlock(program, parcel, wallet.payer, wallet.publicKey, mint);
+    await withdrawUnlocked(
+      program,
+      parcel,
+      wallet.payer,
+      wallet.publicKey,
+      mint
+    );
+
+    const after = await getAccount(connection, depositorAta, 'confirmed');
+    assert.strictEqual(
+      Number(after.amount) - Number(before.amount),
+      assetPrice
+    );
+    assert.isNull(
+      await connection.getAccountInfo(stake.cnftStakeVault, 'confirmed')
+    );
+    assert.isNull(
+      await connection.getAccountInfo(stake.stakeInfo, 'confirmed')
+    );
+
+    const state = await program.account.parcelState.fetch(
+      stake.parcelState,
+      'confirmed'
+    );
+    assert.strictEqual(state.lockedAmount.toNumber(), 0);
+    assert.strictEqual(state.stakers, 0);
+  });
+
+  it('Refunds the deposit from the vault when the cNFT is burned', async () => {
+    const stake = await lockFund(program, tree, parcel, wallet.payer, mint);
+    const before = await getAccount(connection, depositorAta, 'confirmed');
+
+    await burnParcel(program, tree, parcel, wallet.payer, [
+      { depositor: wallet.publicKey, beneficiary: wallet.publicKey, mint },
+    ]);
+
+    const after = await getAccount(connection, depositorAta, 'confirmed');
+    assert.strictEqual(
+      Number(after.amount) - Number(before.amount),
+      assetPrice
+    );
+    assert.isNull(
+      await connection.getAccountInfo(stake.cnftStakeVault, 'confirmed')
+    );
+    assert.isNull(
+      await connection.getAccountInfo(stake.stakeInfo, 'confirmed')
+    );
+    assert.isNull(
+      await connection.getAccountInfo(stake.parcelState, 'confirmed')
+    );
+
+    const collection = await program.account.collectionConfig.fetch(
+      tree.collectionConfig,
+      'confirmed'
+    );
+    assert.strictEqual(collection.live.toNumber(), 0);
+  });
+
+  describe('pooled custody', () => {
+    let pooledParcel: Parcel;
+    let stake: ReturnType<typeof stakeAccounts>;
+
+    const custody = async () =>
+      program.account.pooledCustody.fetch(stake.pooledCustody, 'confirmed');
+    const pooledBalance = async () =>
+      Number(
+        (await getAccount(connection, stake.pooledVault, 'confirmed')).amount
+      );
+
+    before(async () => {
+      pooledParcel = await tree.mint(wallet.publicKey);
+      stake = stakeAccounts(program, pooledParcel, wallet.publicKey, mint);
+      await program.methods
+        .initPooledCustody()
+        .accounts({
+          admin: wallet.publicKey,
+          config: stake.config,
+          pooledCustody: stake.pooledCustody,
+          pooledVault: stake.pooledVault,
+          vaultAuthority: stake.vaultAuthority,
+          whitelist: programPda(program, Buffer.from('token_whitelist')),
+          mint,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: 'confirmed' });
+    });
+
+    it('Locks funds into the mint\'s pooled vault', async () => {
+      await lockFund(program, tree, pooledParcel, wallet.payer, mint, {
+        pooled: true,
+      });
+
+      assert.strictEqual(await pooledBalance(), assetPrice);
+      assert.isNull(
+        await connection.getAccountInfo(stake.cnftStakeVault, 'confirmed')
+      );
+      const ledger = await program.account.stakeInfo.fetch(
+        stake.stakeInfo,
+        'confirmed'
+      );
+      assert.isTrue(ledger.pooled);
+      assert.strictEqual(ledger.amount.toNumber(), assetPrice);
+      const pooled = await custody();
+      assert.strictEqual(pooled.recorded.toNumber(), assetPrice);
+      assert.strictEqual(pooled.entries.toNumber(), 1);
+
+      await program.methods
+        .reconcilePooledCustody()
+        .accounts({
+          pooledCustody: stake.pooledCustody,
+          pooledVault: stake.pooledVault,
+        })
+        .rpc({ commitment: 'confirmed' });
+    });
+
+    it('Withdraws a pooled deposit and keeps the pooled vault', async () => {
+      const before = await getAccount(connection, depositorAta, 'confirmed');
+
+      await requestUnlock(
+        program,
+        pooledParcel,
+        wallet.payer,
+        wallet.publicKey,
+        mint
+      );
+      await withdrawUnlocked(
+        program,
+        pooledParcel,
+        wallet.payer,
+        wallet.publicKey,
+        mint,
+        { pooled: true }
+      );
+
+      const after = await getAccount(connection, depositorAta, 'confirmed');
+      assert.strictEqual(
+        Number(after.amount) - Number(before.amount),
+        assetPrice
+      );
+      assert.strictEqual(await pooledBalance(), 0);
+      const pooled = await custody();
+      assert.strictEqual(pooled.recorded.toNumber(), 0);
+      assert.strictEqual(pooled.entries.toNumber(), 0);
+    });
+
+    it('Refunds a pooled deposit when the cNFT is burned', async () => {
+      await lockFund(program, tree, pooledParcel, wallet.payer, mint, {
+        pooled: true,
+      });
+      const before = await getAccount(connection, depositorAta, 'confirmed');
+
+      await burnParcel(program, tree, pooledParcel, wallet.payer, [
+        {
+          depositor: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          mint,
+          pooled: true,
+        },
+      ]);
+
+      const after = await getAccount(connection, depositorAta, 'confirmed');
+      assert.strictEqual(
+        Number(after.amount) - Number(before.amount),
+        assetPrice
+      );
+      assert.strictEqual(await pooledBalance(), 0);
+      assert.isNull(
+        await connection.getAccountInfo(stake.stakeInfo, 'confirmed')
+      );
+      const pooled = await custody();
+      assert.strictEqual(pooled.recorded.toNumber(), 0);
+      assert.strictEqual(pooled.entries.toNumber(), 0);
+    });
+
+    it('Rejects a pooled vault holding less than its ledger', async () => {
+      // preloaded with a ledger that records more than the vault holds
+      const fixture = (name: string) =>
+        new PublicKey(
+          JSON.parse(
+            fs.readFileSync(`tests/fixtures/short-pool/${name}.json`, 'utf8')
+          ).pubkey
+        );
+
+      await expectError(
+        program.methods
+          .reconcilePooledCustody()
+          .accounts({
+            pooledCustody: fixture('pooled-custody'),
+            pooledVault: fixture('pooled-vault'),
+          })
+          .rpc({ commitment: 'confirmed' }),
+        'PooledVaultOutOfBalance'
+      );
+    });
+  });
+
+  describe('sponsored deposits', () => {
+    // refunded to the sponsor whatever happens to the parcel
+    const depositorRuled = Keypair.generate();
+    // refunded to whoever owns the parcel
+    const ownerRuled = Keypair.generate();
+    let sponsoredParcel: Parcel;
+
+    const balance = async (owner: PublicKey) =>
+      Number(
+        (
+          await getAccount(
+            connection,
+            getAssociatedTokenAddressSync(mint, owner),
+            'confirmed'
+          )
+        ).amount
+      );
+    const sponsor = (
+      sponsorKeypair: Keypair,
+      refundRule: Sponsorship['refundRule']
+    ) =>
+      lockFund(program, tree, sponsoredParcel, sponsorKeypair, mint, {
+        sponsorship: { owner: wallet.publicKey, refundRule },
+      });
+
+    before(async () => {
+      sponsoredParcel = await tree.mint(wallet.publicKey);
+      for (const sponsorKeypair of [depositorRuled, ownerRuled]) {
+        await airdrop(provider, sponsorKeypair.publicKey);
+        const account = await getOrCreateAssociatedTokenAccount(
+          connection,
+          wallet.payer,
+          mint,
+          sponsorKeypair.publicKey
+        );
+        await mintTo(
+          connection,
+          wallet.payer,
+          mint,
+          account.address,
+          wallet.payer,
+          10 * assetPrice
+        );
+      }
+    });
+
+    it('Records the refund beneficiary by refund rule', async () => {
+      await sponsor(depositorRuled, { depositor: {} });
+      await sponsor(ownerRuled, { owner: {} });
+
+      const entry = async (sponsorKeypair: Keypair) =>
+        program.account.stakeInfo.fetch(
+          stakeAccounts(
+            program,
+            sponsoredParcel,
+            sponsorKeypair.publicKey,
+            mint
+          ).stakeInfo,
+          'confirmed'
+        );
+      const sponsored = await entry(depositorRuled);
+      assert.ok(sponsored.depositor.equals(depositorRuled.publicKey));
+      assert.ok(sponsored.owner.equals(wallet.publicKey));
+      assert.ok(sponsored.beneficiary.equals(depositorRuled.publicKey));
+      const owned = await entry(ownerRuled);
+      assert.ok(owned.depositor.equals(ownerRuled.publicKey));
+      assert.ok(owned.owner.equals(wallet.publicKey));
+      assert.ok(owned.beneficiary.equals(wallet.publicKey));
+    });
+
+    it('Rejects a sponsorship for someone else\'s parcel', async () => {
+      const stranger = Keypair.generate().publicKey;
+      await expectError(
+        lockFund(program, tree, sponsoredParcel, wallet.payer, mint, {
+          sponsorship: { owner: stranger, refundRule: { owner: {} } },
+        }),
+        'ConcurrentMerkleTreeError'
+      );
+    });
+
+    it('Unlocks each sponsored deposit to its beneficiary', async () => {
+      // only the sponsor can take back a depositor-refunded deposit
+      await expectError(
+        requestUnlock(
+          program,
+          sponsoredParcel,
+          wallet.payer,
+          depositorRuled.publicKey,
+          mint
+        ),
+        'ConstraintHasOne'
+      );
+
+      const before = await balance(wallet.publicKey);
+      await requestUnlock(
+        program,
+        sponsoredParcel,
+        wallet.payer,
+        ownerRuled.publicKey,
+        mint
+      );
+      await withdrawUnlocked(
+        program,
+        sponsoredParcel,
+        wallet.payer,
+        ownerRuled.publicKey,
+        mint
+      );
+      assert.strictEqual(
+        (await balance(wallet.publicKey)) - before,
+        assetPrice
+      );
+    });
+
+    it('Refunds each sponsored deposit by its rule on burn', async () => {
+      await sponsor(ownerRuled, { owner: {} });
+      const sponsorBefore = await balance(depositorRuled.publicKey);
+      const ownerBefore = await balance(wallet.publicKey);
+
+      await burnParcel(program, tree, sponsoredParcel, wallet.payer, [
+        {
+          depositor: depositorRuled.publicKey,
+          beneficiary: depositorRuled.publicKey,
+          mint,
+        },
+        {
+          depositor: ownerRuled.publicKey,
+          beneficiary: wallet.publicKey,
+          mint,
+        },
+      ]);
+
+      assert.strictEqual(
+        (await balance(depositorRuled.publicKey)) - sponsorBefore,
+        assetPrice
+      );
+      assert.strictEqual(
+        (await balance(wallet.publicKey)) - ownerBefore,
+        assetPrice
+      );
+    });
+  });
+});
@@ -0,0 +1,813 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { Metaplex, keypairIdentity } from '@metaplex-foundation/js';
+import {
+  MPL_BUBBLEGUM_PROGRAM_ID,
+  MetadataArgsArgs,
+  TokenProgramVersion,
+  TokenStandard,
+  getMerkleProofAtIndex,
+  getMerkleRoot,
+  hashLeaf,
+  hashMetadataCreators,
+  hashMetadataData,
+} from '@metaplex-foundation/mpl-bubblegum';
+import { PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID } from '@metaplex-foundation/mpl-token-metadata';
+import {
+  PublicKey as UmiPK,
+  none,
+  publicKey as umiPublicKey,
+  some,
+} from '@metaplex-foundation/umi';
+import { createUmi } from '@metaplex-foundation/umi-bundle-defaults';
+import {
+  ConcurrentMerkleTreeAccount,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+  createAllocTreeIx,
+} from '@solana/spl-account-compression';
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from '@solana/spl-token';
+import {
+  AccountMeta,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from '@solana/web3.js';
+import { assert } from 'chai';
+import fs from 'fs';
+import { MintNftSkytrade } from '../target/types/mint_nft_skytrade';
+
+// Helpers for the tests that run against a local validator
+// (`anchor test --provider.cluster localnet`). Anchor.toml clones bubblegum,
+// account compression, noop and token metadata from devnet, so real cNFTs
+// can be minted.
+
+export const BUBBLEGUM_PROGRAM_ID = new PublicKey(MPL_BUBBLEGUM_PROGRAM_ID);
+
+export const loadKeypair = (path: string) =>
+  Keypair.fromSecretKey(
+    Uint8Array.from(JSON.parse(fs.readFileSync(path, 'utf8')))
+  );
+
+// the Asset valuation value_asset writes for `assetId`
+export const assetInfoFor = (
+  program: Program<MintNftSkytrade>,
+  assetId: PublicKey
+) => programPda(program, Buffer.from('asset'), assetId.toBuffer());
+
+export const sleep = (seconds: number) =>
+  new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+
+// Awaits `call` and checks it failed with the program error `code`.
+export async function expectError(call: Promise<unknown>, code: string) {
+  let error: any;
+  try {
+    await call;
+  } catch (err) {
+    error = err;
+  }
+  assert.ok(error, `expected ${code}`);
+  assert.strictEqual(error.error?.errorCode?.code, code, String(error));
+}
+
+export const programPda = (
+  program: Program<MintNftSkytrade>,
+  ...seeds: Buffer[]
+) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+export async function airdrop(
+  provider: anchor.AnchorProvider,
+  to: PublicKey,
+  sol = 2
+) {
+  const signature = await provider.connection.requestAirdrop(
+    to,
+    sol * anchor.web3.LAMPORTS_PER_SOL
+  );
+  const latest = await provider.connection.getLatestBlockhash();
+  await provider.connection.confirmTransaction(
+    { signature, ...latest },
+    'confirmed'
+  );
+}
+
+// Creates the whitelist and config once per validator; the deploying wallet
+// is the upgrade authority on localnet, so it becomes the admin.
+export async function ensureProgramState(program: Program<MintNftSkytrade>) {
+  const provider = program.provider as anchor.AnchorProvider;
+  const whitelist = programPda(program, Buffer.from('token_whitelist'));
+  const config = programPda(program, Buffer.from('config'));
+  if (!(await provider.connection.getAccountInfo(whitelist))) {
+    await program.methods
+      .init()
+      .accounts({
+        signer: provider.wallet.publicKey,
+        whitelist,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+  }
+  if (!(await provider.connection.getAccountInfo(config))) {
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    );
+    await program.methods
+      .initConfig()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+  }
+  return { whitelist, config };
+}
+
+// Creates a whitelisted payment mint with a treasury and funds `holders`.
+export async function createPaymentMint(
+  program: Program<MintNftSkytrade>,
+  holders: Keypair[],
+  amount: number
+) {
+  const provider = program.provider as anchor.AnchorProvider;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+  const { whitelist, config } = await ensureProgramState(program);
+  const mint = await createMint(
+    provider.connection,
+    payer,
+    payer.publicKey,
+    null,
+    6
+  );
+  const treasury = programPda(
+    program,
+    Buffer.from('treasury'),
+    mint.toBuffer()
+  );
+  const treasuryVault = programPda(
+    program,
+    Buffer.from('treasury_vault'),
+    mint.toBuffer()
+  );
+  await program.methods
+    .whitelistToken()
+    .accounts({
+      signer: payer.publicKey,
+      whitelist,
+      mint,
+      treasury,
+      treasuryVault,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc({ commitment: 'confirmed' });
+  await program.methods
+    .configureTreasury(new anchor.BN(0), new anchor.BN(1))
+    .accounts({
+      admin: payer.publicKey,
+      config,
+      treasury,
+      treasuryVault,
+      whitelist,
+      mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .rpc({ commitment: 'confirmed' });
+
+  const accounts: PublicKey[] = [];
+  for (const holder of holders) {
+    const account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      mint,
+      holder.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      mint,
+      account.address,
+      payer,
+      amount
+    );
+    accounts.push(account.address);
+  }
+  return { mint, treasury, treasuryVault, accounts };
+}
+
+// Sets the protocol fees and unlock cooldown, which every test file shares.
+export async function setConfig(
+  program: Program<MintNftSkytrade>,
+  fees = { mintBps: 0, lockBps: 0, saleBps: 0, leaseBps: 0 },
+  unlockCooldown = 0
+) {
+  const admin = program.provider.publicKey;
+  await program.methods
+    .updateConfig(admin, fees, new anchor.BN(unlockCooldown))
+    .accounts({ admin, config: programPda(program, Buffer.from('config')) })
+    .rpc({ commitment: 'confirmed' });
+}
+
+// Writes the admin's valuation of `assetId` through value_asset.
+export async function valueAsset(
+  program: Program<MintNftSkytrade>,
+  assetId: PublicKey,
+  price: number
+) {
+  const admin = program.provider.publicKey;
+  await program.methods
+    .valueAsset(assetId, {
+      name: 'KONNA',
+      metadataUrl: 'https://arweave.net/Apu1g7uhv52CMeQNfevoody9dVDmaWtQ3TklI6cbNRM',
+      price: new anchor.BN(price),
+      reputation: { medium: {} },
+    })
+    .accounts({
+      admin,
+      config: programPda(program, Buffer.from('config')),
+      assetInfo: assetInfoFor(program, assetId),
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc({ commitment: 'confirmed' });
+}
+
+// Names the arbitrator and sets the early-unlock penalty; the defaults
+// turn the penalty off again.
+export async function setPenaltyPolicy(
+  program: Program<MintNftSkytrade>,
+  arbitrator: PublicKey,
+  minLockDuration = 0,
+  earlyUnlockPenaltyBps = 0
+) {
+  const admin = program.provider.publicKey;
+  await program.methods
+    .updatePenaltyPolicy(arbitrator, {
+      minLockDuration: new anchor.BN(minLockDuration),
+      earlyUnlockPenaltyBps,
+    })
+    .accounts({ admin, config: programPda(program, Buffer.from('config')) })
+    .rpc({ commitment: 'confirmed' });
+}
+
+// The metadata fields a parcel leaf is minted with, as `ParcelMetadata`.
+export interface ParcelMetadata {
+  name: string;
+  symbol: string;
+  uri: string;
+  sellerFeeBasisPoints: number;
+  primarySaleHappened: boolean;
+  isMutable: boolean;
+}
+
+export interface Parcel {
+  assetId: PublicKey;
+  nonce: number;
+  owner: PublicKey;
+  delegate: PublicKey;
+  metadata: ParcelMetadata;
+  burned: boolean;
+}
+
+// A merkle tree minted through mint_cnft whose leaves are mirrored locally,
+// so proofs can be built without an indexer. The tree keypair is a fixture
+// so the asset ids are stable.
+export class ParcelTree {
+  static readonly maxDepth = 5;
+  static readonly maxBufferSize = 8;
+  // what every minted parcel is valued at, so funds can be locked against it
+  static readonly assetPrice = 1_000_000;
+
+  readonly merkleTree: PublicKey;
+  readonly treeConfig: PublicKey;
+  readonly treeOwner: PublicKey;
+  collectionMint: PublicKey;
+  collectionMetadata: PublicKey;
+  collectionEdition: PublicKey;
+  collectionConfig: PublicKey;
+  readonly parcels: Parcel[] = [];
+
+  private readonly umi: ReturnType<typeof createUmi>;
+
+  constructor(
+    private readonly program: Program<MintNftSkytrade>,
+    private readonly treeKeypair: Keypair
+  ) {
+    this.merkleTree = treeKeypair.publicKey;
+    [this.treeConfig] = PublicKey.findProgramAddressSync(
+      [this.merkleTree.toBuffer()],
+      BUBBLEGUM_PROGRAM_ID
+    );
+    this.treeOwner = programPda(
+      program,
+      Buffer.from('tree_owner'),
+      this.merkleTree.toBuffer()
+    );
+    this.umi = createUmi(
+      (program.provider as anchor.AnchorProvider).connection.rpcEndpoint
+    );
+  }
+
+  private get provider() {
+    return this.program.provider as anchor.AnchorProvider;
+  }
+
+  private get payer() {
+    return (this.provider.wallet as anchor.Wallet).payer;
+  }
+
+  // Creates the collection, the tree and the collection config.
+  async init(symbol = 'KNA') {
+    const { config } = await ensureProgramState(this.program);
+    const metaplex = Metaplex.make(this.provider.connection).use(
+      keypairIdentity(this.payer)
+    );
+    const collectionNft = await metaplex.nfts().create({
+      uri: 'https://arweave.net/h19GMcMz7RLDY7kAHGWeWolHTmO83mLLMNPzEkF32BQ',
+      name: 'SKY-TRADE',
+      symbol: 'SKY-T',
+      sellerFeeBasisPoints: 0,
+      isCollection: true,
+    });
+    // the tree owner pda signs as the collection authority when minting
+    await metaplex.nfts().update({
+      nftOrSft: collectionNft.nft,
+      updateAuthority: this.payer,
+      newUpdateAuthority: this.treeOwner,
+    });
+    this.collectionMint = collectionNft.mintAddress;
+    this.collectionMetadata = collectionNft.metadataAddress;
+    this.collectionEdition = collectionNft.masterEditionAddress;
+    this.collectionConfig = programPda(
+      this.program,
+      Buffer.from('collection_config'),
+      this.collectionMint.toBuffer()
+    );
+
+    const allocTreeIx = await createAllocTreeIx(
+      this.provider.connection,
+      this.merkleTree,
+      this.payer.publicKey,
+      {
+        maxDepth: ParcelTree.maxDepth,
+        maxBufferSize: ParcelTree.maxBufferSize,
+      },
+      0
+    );
+    await sendAndConfirmTransaction(
+      this.provider.connection,
+      new Transaction().add(allocTreeIx),
+      [this.payer, this.treeKeypair],
+      { commitment: 'confirmed' }
+    );
+    await this.program.methods
+      .createTree(ParcelTree.maxDepth, ParcelTree.maxBufferSize)
+      .accounts({
+        signer: this.payer.publicKey,
+        treeConfig: this.treeConfig,
+        merkleTree: this.merkleTree,
+        treeOwner: this.treeOwner,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+        mplBubblegumProgram: MPL_BUBBLEGUM_PROGRAM_ID,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      })
+      .rpc({ commitment: 'confirmed' });
+    await this.program.methods
+      .initCollection(null, symbol, 0b111)
+      .accounts({
+        admin: this.payer.publicKey,
+        config,
+        collectionConfig: this.collectionConfig,
+        collectionMint: this.collectionMint,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+  }
+
+  assetId(nonce: number) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('asset', 'utf8'),
+        this.merkleTree.toBuffer(),
+        new anchor.BN(nonce).toArrayLike(Buffer, 'le', 8),
+      ],
+      BUBBLEGUM_PROGRAM_ID
+    )[0];
+  }
+
+  // Mints the next leaf to `owner` through mint_cnft and values it at
+  // `ParcelTree.assetPrice`.
+  async mint(owner: PublicKey, name = 'KONNA'): Promise<Parcel> {
+    const requestId = Array.from(Keypair.generate().publicKey.toBytes());
+    const metadata: ParcelMetadata = {
+      name,
+      symbol: 'KNA',
+      uri: 'https://arweave.net/Apu1g7uhv52CMeQNfevoody9dVDmaWtQ3TklI6cbNRM',
+      sellerFeeBasisPoints: 0,
+      primarySaleHappened: false,
+      isMutable: true,
+    };
+    await this.program.methods
+      .mintCnft(
+        requestId,
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+        metadata.sellerFeeBasisPoints
+      )
+      .accounts({
+        payer: this.payer.publicKey,
+        mintReceipt: programPda(
+          this.program,
+          Buffer.from('mint_receipt'),
+          Buffer.from(requestId)
+        ),
+        treeConfig: this.treeConfig,
+        leafOwner: owner,
+        merkleTree: this.merkleTree,
+        treeDelegate: this.payer.publicKey,
+        centralAuthority: this.treeOwner,
+        collectionMint: this.collectionMint,
+        collectionConfig: this.collectionConfig,
+        config: programPda(this.program, Buffer.from('config')),
+        assetInfo: null,
+        payerTokenAccount: null,
+        treasuryVault: null,
+        tokenProgram: null,
+        collectionMetadata: this.collectionMetadata,
+        editionAccount: this.collectionEdition,
+        bubblegumSigner: PublicKey.findProgramAddressSync(
+          [Buffer.from('collection_cpi', 'utf8')],
+          BUBBLEGUM_PROGRAM_ID
+        )[0],
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        bubblegumProgram: MPL_BUBBLEGUM_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    const nonce = this.parcels.length;
+    const parcel: Parcel = {
+      assetId: this.assetId(nonce),
+      nonce,
+      owner,
+      delegate: owner,
+      metadata,
+      burned: false,
+    };
+    this.parcels.push(parcel);
+    await valueAsset(this.program, parcel.assetId, ParcelTree.assetPrice);
+    return parcel;
+  }
+
+  // The full bubblegum metadata mint_cnft builds from `ParcelMetadata`.
+  metadataArgs(metadata: ParcelMetadata): MetadataArgsArgs {
+    return {
+      ...metadata,
+      creators: [],
+      editionNonce: some(0),
+      uses: none(),
+      collection: some({
+        verified: true,
+        key: this.collectionMint.toBase58() as UmiPK,
+      }),
+      tokenProgramVersion: TokenProgramVersion.Original,
+      tokenStandard: some(TokenStandard.NonFungible),
+    };
+  }
+
+  dataHash(parcel: Parcel) {
+    return Array.from(hashMetadataData(this.metadataArgs(parcel.metadata)));
+  }
+
+  creatorHash() {
+    return Array.from(hashMetadataCreators([]));
+  }
+
+  private leaves(): UmiPK[] {
+    return this.parcels.map((parcel) =>
+      parcel.burned
+        ? umiPublicKey(new Uint8Array(32))
+        : umiPublicKey(
+            hashLeaf(this.umi, {
+              merkleTree: this.merkleTree.toBase58() as UmiPK,
+              owner: parcel.owner.toBase58() as UmiPK,
+              delegate: parcel.delegate.toBase58() as UmiPK,
+              leafIndex: parcel.nonce,
+              metadata: this.metadataArgs(parcel.metadata),
+            })
+          )
+    );
+  }
+
+  root() {
+    const root = getMerkleRoot(this.leaves(), ParcelTree.maxDepth);
+    return Array.from(new PublicKey(root).toBytes());
+  }
+
+  // The root bubblegum holds, to check the mirrored leaves against.
+  async onChainRoot() {
+    const account = await ConcurrentMerkleTreeAccount.fromAccountAddress(
+      this.provider.connection,
+      this.merkleTree,
+      'confirmed'
+    );
+    return Array.from(account.getCurrentRoot());
+  }
+
+  proof(parcel: Parcel): AccountMeta[] {
+    return getMerkleProofAtIndex(
+      this.leaves(),
+      ParcelTree.maxDepth,
+      parcel.nonce
+    ).map((node) => ({
+      pubkey: new PublicKey(node),
+      isSigner: false,
+      isWritable: false,
+    }));
+  }
+
+  // The arguments every leaf-verifying instruction takes, from the
+  // locally mirrored leaf.
+  leaf(parcel: Parcel) {
+    return {
+      root: this.root(),
+      dataHash: this.dataHash(parcel),
+      creatorHash: this.creatorHash(),
+      nonce: new anchor.BN(parcel.nonce),
+      index: parcel.nonce,
+      proof: this.proof(parcel),
+    };
+  }
+
+  // The tree accounts bubblegum CPIs take, under this program's names.
+  treeAccounts() {
+    return {
+      merkleTree: this.merkleTree,
+      treeConfig: this.treeConfig,
+      logWrapper: SPL_NOOP_PROGRAM_ID,
+      compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      bubblegumProgram: MPL_BUBBLEGUM_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  }
+
+  // Mirrors a leaf change made on chain.
+  setOwner(parcel: Parcel, owner: PublicKey) {
+    parcel.owner = owner;
+    parcel.delegate = owner;
+  }
+
+  setDelegate(parcel: Parcel, delegate: PublicKey) {
+    parcel.delegate = delegate;
+  }
+
+  burn(parcel: Parcel) {
+    parcel.burned = true;
+  }
+}
+
+// The accounts a stake of `depositor` in `mint` against `parcel` lives in.
+export function stakeAccounts(
+  program: Program<MintNftSkytrade>,
+  parcel: Parcel,
+  depositor: PublicKey,
+  mint: PublicKey
+) {
+  const cnft = parcel.assetId.toBuffer();
+  return {
+    stakeInfo: programPda(
+      program,
+      Buffer.from('stake_info'),
+      cnft,
+      depositor.toBuffer(),
+      mint.toBuffer()
+    ),
+    cnftStakeVault: programPda(
+      program,
+      Buffer.from('stake_vault'),
+      cnft,
+      mint.toBuffer()
+    ),
+    pooledCustody: programPda(
+      program,
+      Buffer.from('pooled_custody'),
+      mint.toBuffer()
+    ),
+    pooledVault: programPda(
+      program,
+      Buffer.from('pooled_vault'),
+      mint.toBuffer()
+    ),
+    parcelState: programPda(program, Buffer.from('parcel_state'), cnft),
+    rewardPool: programPda(
+      program,
+      Buffer.from('reward_pool'),
+      mint.toBuffer()
+    ),
+    treasuryVault: programPda(
+      program,
+      Buffer.from('treasury_vault'),
+      mint.toBuffer()
+    ),
+    vaultAuthority: programPda(program, Buffer.from('vault_authority')),
+    config: programPda(program, Buffer.from('config')),
+  };
+}
+
+// Names the parcel owner a sponsored deposit backs, as `Sponsorship`.
+export interface Sponsorship {
+  owner: PublicKey;
+  refundRule: { depositor: {} } | { owner: {} };
+}
+
+// Locks the parcel's asset price through lock_fund, in the per-cNFT vault
+// unless `pooled` is set.
+export async function lockFund(
+  program: Program<MintNftSkytrade>,
+  tree: ParcelTree,
+  parcel: Parcel,
+  depositor: Keypair,
+  mint: PublicKey,
+  {
+    pooled = false,
+    sponsorship = null,
+  }: { pooled?: boolean; sponsorship?: Sponsorship | null } = {}
+) {
+  const stake = stakeAccounts(program, parcel, depositor.publicKey, mint);
+  const leaf = tree.leaf(parcel);
+  await program.methods
+    .lockFund(
+      leaf.root,
+      leaf.dataHash,
+      leaf.creatorHash,
+      leaf.nonce,
+      leaf.index,
+      sponsorship
+    )
+    .accounts({
+      signer: depositor.publicKey,
+      leafDelegate: parcel.delegate,
+      cnft: parcel.assetId,
+      assetInfo: assetInfoFor(program, parcel.assetId),
+      signerTokenAta: getAssociatedTokenAddressSync(mint, depositor.publicKey),
+      cnftStakeVault: pooled ? null : stake.cnftStakeVault,
+      pooledCustody: pooled ? stake.pooledCustody : null,
+      pooledVault: pooled ? stake.pooledVault : null,
+      vaultAuthority: stake.vaultAuthority,
+      parcelState: stake.parcelState,
+      stakeInfo: stake.stakeInfo,
+      rewardPool: stake.rewardPool,
+      whitelist: programPda(program, Buffer.from('token_whitelist')),
+      config: stake.config,
+      treasuryVault: stake.treasuryVault,
+      txTokenMint: mint,
+      merkleTree: tree.merkleTree,
+      compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(leaf.proof)
+    .signers([depositor])
+    .rpc({ commitment: 'confirmed' });
+  return stake;
+}
+
+export async function requestUnlock(
+  program: Program<MintNftSkytrade>,
+  parcel: Parcel,
+  beneficiary: Keypair,
+  depositor: PublicKey,
+  mint: PublicKey
+) {
+  const stake = stakeAccounts(program, parcel, depositor, mint);
+  await program.methods
+    .requestUnlock()
+    .accounts({
+      beneficiary: beneficiary.publicKey,
+      stakeInfo: stake.stakeInfo,
+      config: stake.config,
+    })
+    .signers([beneficiary])
+    .rpc({ commitment: 'confirmed' });
+}
+
+// Withdraws a requested unlock to the beneficiary's associated token
+// account. `penaltyRecord` is only passed for early exits.
+export async function withdrawUnlocked(
+  program: Program<MintNftSkytrade>,
+  parcel: Parcel,
+  beneficiary: Keypair,
+  depositor: PublicKey,
+  mint: PublicKey,
+  {
+    pooled = false,
+    penaltyRecord = null,
+  }: { pooled?: boolean; penaltyRecord?: PublicKey | null } = {}
+) {
+  const stake = stakeAccounts(program, parcel, depositor, mint);
+  await program.methods
+    .withdrawUnlocked()
+    .accounts({
+      beneficiary: beneficiary.publicKey,
+      depositor,
+      stakeInfo: stake.stakeInfo,
+      parcelState: stake.parcelState,
+      cnftStakeVault: pooled ? null : stake.cnftStakeVault,
+      pooledCustody: pooled ? stake.pooledCustody : null,
+      pooledVault: pooled ? stake.pooledVault : null,
+      vaultAuthority: stake.vaultAuthority,
+      rewardPool: stake.rewardPool,
+      beneficiaryTokenAccount: getAssociatedTokenAddressSync(
+        mint,
+        beneficiary.publicKey
+      ),
+      config: stake.config,
+      treasuryVault: stake.treasuryVault,
+      penaltyRecord,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([beneficiary])
+    .rpc({ commitment: 'confirmed' });
+}
+
+export interface BurnLedgerEntry {
+  depositor: PublicKey;
+  beneficiary: PublicKey;
+  mint: PublicKey;
+  pooled?: boolean;
+}
+
+// Burns the parcel through burn_cnft, passing one ledger group per open
+// stake after the proof.
+export async function burnParcel(
+  program: Program<MintNftSkytrade>,
+  tree: ParcelTree,
+  parcel: Parcel,
+  owner: Keypair,
+  entries: BurnLedgerEntry[]
+) {
+  const leaf = tree.leaf(parcel);
+  const writable = (pubkey: PublicKey) => ({
+    pubkey,
+    isSigner: false,
+    isWritable: true,
+  });
+  const ledger = entries.reduce((accounts, entry) => {
+    const stake = stakeAccounts(program, parcel, entry.depositor, entry.mint);
+    return accounts.concat([
+      writable(stake.stakeInfo),
+      writable(entry.pooled ? stake.pooledVault : stake.cnftStakeVault),
+      writable(getAssociatedTokenAddressSync(entry.mint, entry.beneficiary)),
+      { pubkey: entry.beneficiary, isSigner: false, isWritable: false },
+      { pubkey: entry.mint, isSigner: false, isWritable: false },
+      writable(entry.depositor),
+      writable(stake.rewardPool),
+      writable(stake.pooledCustody),
+      writable(getAssociatedTokenAddressSync(entry.mint, owner.publicKey)),
+    ]);
+  }, [] as AccountMeta[]);
+
+  await program.methods
+    .burnCnft(
+      leaf.root,
+      parcel.metadata,
+      leaf.creatorHash,
+      leaf.nonce,
+      leaf.index
+    )
+    .accounts({
+      signer: owner.publicKey,
+      leafDelegate: parcel.delegate,
+      cnft: parcel.assetId,
+      vaultAuthority: programPda(program, Buffer.from('vault_authority')),
+      parcelState: programPda(
+        program,
+        Buffer.from('parcel_state'),
+        parcel.assetId.toBuffer()
+      ),
+      collectionConfig: tree.collectionConfig,
+      ...tree.treeAccounts(),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(leaf.proof.concat(ledger))
+    .signers([owner])
+    .rpc({ commitment: 'confirmed' });
+  tree.burn(parcel);
+}